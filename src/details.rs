@@ -1,3 +1,6 @@
 pub mod angular_momentum;
 pub mod atomic_basis_set;
+pub mod basis_set_assignment;
+pub mod ecp;
 pub mod gaussian_exp;
+pub mod library;
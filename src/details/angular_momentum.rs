@@ -1,9 +1,11 @@
 /// Angular momentum
+use std::error::Error;
 
 use strum_macros::Display;
 
 #[repr(i8)]
 #[derive(Debug, Display, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AngularMomentum {
     S = 0,
     P = 1,
@@ -11,75 +13,173 @@ pub enum AngularMomentum {
     F = 3,
     G = 4,
     H = 5,
+    I = 6,
+    K = 7,
 
     UnsupportedAngularMomentum = -1,
 }
 
-impl From<char> for AngularMomentum {
-    fn from(ch: char) -> Self {
+#[derive(Debug, PartialEq, Eq)]
+pub enum AngularMomentumError {
+    // The character is not a known spectroscopic letter
+    UnknownSymbol(char),
+    // The value is above the highest supported angular momentum
+    OutOfRange(usize),
+}
+
+impl std::fmt::Display for AngularMomentumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AngularMomentumError::UnknownSymbol(ch) => {
+                write!(f, "Unknown angular momentum symbol '{}'", ch)
+            }
+            AngularMomentumError::OutOfRange(us) => {
+                write!(f, "Unsupported angular momentum l={}", us)
+            }
+        }
+    }
+}
+
+impl Error for AngularMomentumError {}
+
+impl TryFrom<char> for AngularMomentum {
+    type Error = AngularMomentumError;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
         match ch {
-            'S' | 's' => AngularMomentum::S,
-            'P' | 'p' => AngularMomentum::P,
-            'D' | 'd' => AngularMomentum::D,
-            'F' | 'f' => AngularMomentum::F,
-            'G' | 'g' => AngularMomentum::G,
-            'H' | 'h' => AngularMomentum::H,
-            _ => AngularMomentum::UnsupportedAngularMomentum,
+            'S' | 's' => Ok(AngularMomentum::S),
+            'P' | 'p' => Ok(AngularMomentum::P),
+            'D' | 'd' => Ok(AngularMomentum::D),
+            'F' | 'f' => Ok(AngularMomentum::F),
+            'G' | 'g' => Ok(AngularMomentum::G),
+            'H' | 'h' => Ok(AngularMomentum::H),
+            'I' | 'i' => Ok(AngularMomentum::I),
+            'K' | 'k' => Ok(AngularMomentum::K),
+            _ => Err(AngularMomentumError::UnknownSymbol(ch)),
         }
     }
 }
 
-impl From<usize> for AngularMomentum {
-    fn from(us: usize) -> Self {
+impl TryFrom<usize> for AngularMomentum {
+    type Error = AngularMomentumError;
+
+    fn try_from(us: usize) -> Result<Self, Self::Error> {
         match us {
-            0 => AngularMomentum::S,
-            1 => AngularMomentum::P,
-            2 => AngularMomentum::D,
-            3 => AngularMomentum::F,
-            4 => AngularMomentum::G,
-            5 => AngularMomentum::H,
-            _ => AngularMomentum::UnsupportedAngularMomentum,
+            0 => Ok(AngularMomentum::S),
+            1 => Ok(AngularMomentum::P),
+            2 => Ok(AngularMomentum::D),
+            3 => Ok(AngularMomentum::F),
+            4 => Ok(AngularMomentum::G),
+            5 => Ok(AngularMomentum::H),
+            6 => Ok(AngularMomentum::I),
+            7 => Ok(AngularMomentum::K),
+            _ => Err(AngularMomentumError::OutOfRange(us)),
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::AngularMomentum;
+impl AngularMomentum {
+    /// Every supported angular momentum in increasing order, without the unsupported sentinel
+    pub fn all() -> &'static [AngularMomentum] {
+        &[
+            AngularMomentum::S,
+            AngularMomentum::P,
+            AngularMomentum::D,
+            AngularMomentum::F,
+            AngularMomentum::G,
+            AngularMomentum::H,
+            AngularMomentum::I,
+            AngularMomentum::K,
+        ]
+    }
 
-    #[test]
-    fn test_to_angular_momentums() {
-        assert_eq!(AngularMomentum::from('S'), AngularMomentum::S);
-        assert_eq!(AngularMomentum::from('s'), AngularMomentum::S);
-        assert_eq!(AngularMomentum::from(0), AngularMomentum::S);
+    /// Spectroscopic letter of the angular momentum, the inverse of `TryFrom<char>`.
+    /// `UnsupportedAngularMomentum` is rendered as `'?'`.
+    pub fn symbol(&self) -> char {
+        match self {
+            AngularMomentum::S => 'S',
+            AngularMomentum::P => 'P',
+            AngularMomentum::D => 'D',
+            AngularMomentum::F => 'F',
+            AngularMomentum::G => 'G',
+            AngularMomentum::H => 'H',
+            AngularMomentum::I => 'I',
+            AngularMomentum::K => 'K',
+            AngularMomentum::UnsupportedAngularMomentum => '?',
+        }
+    }
 
-        assert_eq!(AngularMomentum::from('P'), AngularMomentum::P);
-        assert_eq!(AngularMomentum::from('p'), AngularMomentum::P);
-        assert_eq!(AngularMomentum::from(1), AngularMomentum::P);
+    /// Number of Cartesian Gaussian components of a shell, (l + 1)(l + 2) / 2
+    pub fn num_cartesian_functions(&self) -> usize {
+        match self {
+            AngularMomentum::UnsupportedAngularMomentum => 0,
+            _ => {
+                let l = *self as usize;
+                (l + 1) * (l + 2) / 2
+            }
+        }
+    }
 
-        assert_eq!(AngularMomentum::from('D'), AngularMomentum::D);
-        assert_eq!(AngularMomentum::from('d'), AngularMomentum::D);
-        assert_eq!(AngularMomentum::from(2), AngularMomentum::D);
+    /// Number of pure spherical harmonic components of a shell, 2l + 1
+    pub fn num_spherical_functions(&self) -> usize {
+        match self {
+            AngularMomentum::UnsupportedAngularMomentum => 0,
+            _ => 2 * (*self as usize) + 1,
+        }
+    }
 
-        assert_eq!(AngularMomentum::from('F'), AngularMomentum::F);
-        assert_eq!(AngularMomentum::from('f'), AngularMomentum::F);
-        assert_eq!(AngularMomentum::from(3), AngularMomentum::F);
+    /// Exponents (lx, ly, lz) of the Cartesian components x^lx y^ly z^lz of a shell, in the
+    /// lexicographic order shared by Gaussian, libint and most integral codes: lx descending,
+    /// then ly descending, e.g. xx, xy, xz, yy, yz, zz for D.
+    pub fn cartesian_components(&self) -> Vec<(u8, u8, u8)> {
+        if *self == AngularMomentum::UnsupportedAngularMomentum {
+            return vec![];
+        }
+        let l = *self as u8;
+        let mut components = Vec::with_capacity(self.num_cartesian_functions());
+        for lx in (0..=l).rev() {
+            for ly in (0..=l - lx).rev() {
+                components.push((lx, ly, l - lx - ly));
+            }
+        }
+        components
+    }
+}
 
-        assert_eq!(AngularMomentum::from('G'), AngularMomentum::G);
-        assert_eq!(AngularMomentum::from('g'), AngularMomentum::G);
-        assert_eq!(AngularMomentum::from(4), AngularMomentum::G);
+#[cfg(test)]
+mod tests {
+    use super::{AngularMomentum, AngularMomentumError};
 
-        assert_eq!(AngularMomentum::from('H'), AngularMomentum::H);
-        assert_eq!(AngularMomentum::from('h'), AngularMomentum::H);
-        assert_eq!(AngularMomentum::from(5), AngularMomentum::H);
+    #[test]
+    fn test_to_angular_momentums() {
+        for (letters, l, expected) in [
+            ("Ss", 0, AngularMomentum::S),
+            ("Pp", 1, AngularMomentum::P),
+            ("Dd", 2, AngularMomentum::D),
+            ("Ff", 3, AngularMomentum::F),
+            ("Gg", 4, AngularMomentum::G),
+            ("Hh", 5, AngularMomentum::H),
+            ("Ii", 6, AngularMomentum::I),
+            ("Kk", 7, AngularMomentum::K),
+        ] {
+            for letter in letters.chars() {
+                assert_eq!(AngularMomentum::try_from(letter), Ok(expected));
+            }
+            assert_eq!(AngularMomentum::try_from(l), Ok(expected));
+        }
 
+        // The chemistry convention skips J
+        assert_eq!(
+            AngularMomentum::try_from('J'),
+            Err(AngularMomentumError::UnknownSymbol('J'))
+        );
         assert_eq!(
-            AngularMomentum::from('T'),
-            AngularMomentum::UnsupportedAngularMomentum
+            AngularMomentum::try_from('T'),
+            Err(AngularMomentumError::UnknownSymbol('T'))
         );
         assert_eq!(
-            AngularMomentum::from(6),
-            AngularMomentum::UnsupportedAngularMomentum
+            AngularMomentum::try_from(8),
+            Err(AngularMomentumError::OutOfRange(8))
         );
     }
 
@@ -91,5 +191,104 @@ mod tests {
         assert_eq!(AngularMomentum::F as i8, 3);
         assert_eq!(AngularMomentum::G as i8, 4);
         assert_eq!(AngularMomentum::H as i8, 5);
+        assert_eq!(AngularMomentum::I as i8, 6);
+        assert_eq!(AngularMomentum::K as i8, 7);
+    }
+
+    #[test]
+    fn test_symbol() {
+        for angular_momentum in [
+            AngularMomentum::S,
+            AngularMomentum::P,
+            AngularMomentum::D,
+            AngularMomentum::F,
+            AngularMomentum::G,
+            AngularMomentum::H,
+            AngularMomentum::I,
+            AngularMomentum::K,
+        ] {
+            assert_eq!(
+                AngularMomentum::try_from(angular_momentum.symbol()),
+                Ok(angular_momentum)
+            );
+        }
+
+        assert_eq!(AngularMomentum::D.symbol(), 'D');
+        assert_eq!(AngularMomentum::UnsupportedAngularMomentum.symbol(), '?');
+    }
+
+    #[test]
+    fn test_num_cartesian_functions() {
+        for (angular_momentum, expected) in [
+            (AngularMomentum::S, 1),
+            (AngularMomentum::P, 3),
+            (AngularMomentum::D, 6),
+            (AngularMomentum::F, 10),
+            (AngularMomentum::G, 15),
+            (AngularMomentum::H, 21),
+            (AngularMomentum::I, 28),
+            (AngularMomentum::K, 36),
+            (AngularMomentum::UnsupportedAngularMomentum, 0),
+        ] {
+            assert_eq!(angular_momentum.num_cartesian_functions(), expected);
+        }
+    }
+
+    #[test]
+    fn test_num_spherical_functions() {
+        for (angular_momentum, expected) in [
+            (AngularMomentum::S, 1),
+            (AngularMomentum::P, 3),
+            (AngularMomentum::D, 5),
+            (AngularMomentum::F, 7),
+            (AngularMomentum::G, 9),
+            (AngularMomentum::H, 11),
+            (AngularMomentum::I, 13),
+            (AngularMomentum::K, 15),
+            (AngularMomentum::UnsupportedAngularMomentum, 0),
+        ] {
+            assert_eq!(angular_momentum.num_spherical_functions(), expected);
+        }
+
+        // Cartesian and spherical conventions start to differ from D
+        assert_eq!(AngularMomentum::D.num_spherical_functions(), 5);
+        assert_eq!(AngularMomentum::D.num_cartesian_functions(), 6);
+    }
+
+    #[test]
+    fn test_cartesian_components() {
+        assert_eq!(AngularMomentum::S.cartesian_components(), vec![(0, 0, 0)]);
+        assert_eq!(
+            AngularMomentum::P.cartesian_components(),
+            vec![(1, 0, 0), (0, 1, 0), (0, 0, 1)]
+        );
+        assert_eq!(
+            AngularMomentum::D.cartesian_components(),
+            vec![
+                (2, 0, 0),
+                (1, 1, 0),
+                (1, 0, 1),
+                (0, 2, 0),
+                (0, 1, 1),
+                (0, 0, 2)
+            ]
+        );
+        let f_components = AngularMomentum::F.cartesian_components();
+        assert_eq!(f_components.len(), 10);
+        assert!(f_components.iter().all(|(lx, ly, lz)| lx + ly + lz == 3));
+        assert!(AngularMomentum::UnsupportedAngularMomentum
+            .cartesian_components()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_all() {
+        let all = AngularMomentum::all();
+        assert_eq!(all.len(), 8);
+        for (l, angular_momentum) in all.iter().enumerate() {
+            assert_eq!(AngularMomentum::try_from(l), Ok(*angular_momentum));
+        }
+        assert!(AngularMomentum::try_from(all.len()).is_err());
+        assert!(!all.contains(&AngularMomentum::UnsupportedAngularMomentum));
     }
 }
@@ -1,23 +1,215 @@
-use std::vec::Vec;
+use std::{collections::BTreeMap, error::Error, vec::Vec};
 
-use super::{angular_momentum::AngularMomentum, gaussian_exp::SegmentedContraction};
+use super::{
+    angular_momentum::AngularMomentum,
+    gaussian_exp::{
+        overlap_same_center, relative_eq, GaussianPrimitive, GeneralContraction,
+        SegmentedContraction,
+    },
+};
 
-pub struct AtomicBasisSet(Vec<Vec<SegmentedContraction>>);
+// Relative tolerance below which two exponentals read from text are taken as the same
+const EXPONENTAL_RELATIVE_TOLERANCE: f64 = 1e-8;
+
+// Angular momentum of a slot of the contractions, slots past the highest supported one only
+// come from deserialized data
+fn slot_angular_momentum(angular_momentum_num: usize) -> AngularMomentum {
+    AngularMomentum::try_from(angular_momentum_num)
+        .unwrap_or(AngularMomentum::UnsupportedAngularMomentum)
+}
+
+// Parameters of the 64-bit FNV-1a hash
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+fn fnv1a(hash: u64, value: u64) -> u64 {
+    value.to_le_bytes().iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BasisSetValidationError {
+    // The coefficients and exponentals of a shell differ in length
+    MismatchedLengths {
+        num_coefficients: usize,
+        num_exponentals: usize,
+    },
+    // The shell has no supported angular momentum
+    UnsupportedAngularMomentum,
+    // A row of a combined shell whose number of columns does not match the shell
+    BadRowLength {
+        row: usize,
+        num_columns: usize,
+    },
+    // The contraction holds no primitive
+    EmptyContraction {
+        angular_momentum: AngularMomentum,
+        index: usize,
+    },
+    // An exponental is zero or negative, usually a sign of swapped columns
+    NonPositiveExponental {
+        angular_momentum: AngularMomentum,
+        index: usize,
+        exponental: f64,
+    },
+}
+
+impl std::fmt::Display for BasisSetValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BasisSetValidationError::MismatchedLengths {
+                num_coefficients,
+                num_exponentals,
+            } => write!(
+                f,
+                "{} coefficients given for {} exponentals",
+                num_coefficients, num_exponentals
+            ),
+            BasisSetValidationError::UnsupportedAngularMomentum => {
+                write!(f, "Unsupported angular momentum")
+            }
+            BasisSetValidationError::BadRowLength { row, num_columns } => {
+                write!(f, "Row {} of the shell has {} columns", row, num_columns)
+            }
+            BasisSetValidationError::EmptyContraction {
+                angular_momentum,
+                index,
+            } => write!(f, "{} contraction {} is empty", angular_momentum, index),
+            BasisSetValidationError::NonPositiveExponental {
+                angular_momentum,
+                index,
+                exponental,
+            } => write!(
+                f,
+                "{} contraction {} has non-positive exponental {}",
+                angular_momentum, index, exponental
+            ),
+        }
+    }
+}
+
+impl Error for BasisSetValidationError {}
+
+/// Whether a basis set is meant to be used with pure spherical harmonic components (e.g. 5d) or
+/// with Cartesian components (e.g. 6d)
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SphericalOrCartesian {
+    #[default]
+    Spherical,
+    Cartesian,
+}
+
+/// Differences between two basis sets, as found by `AtomicBasisSet::diff`. Contractions are
+/// identified by their angular momentum and index within it, and are paired by that position.
+#[derive(Debug, Default, PartialEq)]
+pub struct BasisSetDiff {
+    /// Contractions only found in the other basis set
+    pub added: Vec<(AngularMomentum, usize)>,
+    /// Contractions only found in the first basis set
+    pub removed: Vec<(AngularMomentum, usize)>,
+    /// Contractions found in both but with different primitives, along with the indices of the
+    /// primitives that differ or are missing from either side
+    pub modified: Vec<(AngularMomentum, usize, Vec<usize>)>,
+}
+
+impl BasisSetDiff {
+    /// Whether the basis sets were found equal
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Counts describing a basis set, as given by `AtomicBasisSet::summary`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasisSetSummary {
+    /// Number of contracted functions
+    pub num_shells: usize,
+    pub num_primitives: usize,
+    /// `UnsupportedAngularMomentum` for an empty basis set
+    pub highest_angular_momentum: AngularMomentum,
+    /// Number of contracted functions under each angular momentum holding contractions
+    pub shells_per_angular_momentum: Vec<(AngularMomentum, usize)>,
+}
+
+/// One line summary, e.g. `S:4 P:3 (7 shells, 16 primitives, max=P)`
+impl std::fmt::Display for BasisSetSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (angular_momentum, num_shells) in self.shells_per_angular_momentum.iter() {
+            write!(f, "{}:{} ", angular_momentum.symbol(), num_shells)?;
+        }
+        write!(
+            f,
+            "({} shells, {} primitives, max={})",
+            self.num_shells,
+            self.num_primitives,
+            self.highest_angular_momentum.symbol()
+        )
+    }
+}
+
+/// Compares exactly, contraction by contraction
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtomicBasisSet {
+    // Segmented contractions indexed by angular momentum
+    contractions: Vec<Vec<SegmentedContraction>>,
+    spherical: SphericalOrCartesian,
+    // Integer closing the declaration line of the Gaussian94 format, e.g. the 0 of `C 0`
+    declaration_flag: i32,
+}
+
+/// Empty slots past the highest angular momentum holding a contraction, e.g. reserved by
+/// `with_capacity`, do not count
+impl PartialEq for AtomicBasisSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.occupied_contractions() == other.occupied_contractions()
+            && self.spherical == other.spherical
+            && self.declaration_flag == other.declaration_flag
+    }
+}
+
+impl Default for AtomicBasisSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl AtomicBasisSet {
     pub fn new() -> Self {
-        AtomicBasisSet(vec![])
+        AtomicBasisSet {
+            contractions: vec![],
+            spherical: SphericalOrCartesian::default(),
+            declaration_flag: 0,
+        }
+    }
+
+    /// Empty basis set with room for `shells_per_l` contractions under every angular momentum
+    /// up to `max_angular_momentum`. The reserved slots stay invisible until a contraction is
+    /// added to them.
+    pub fn with_capacity(max_angular_momentum: AngularMomentum, shells_per_l: usize) -> Self {
+        let num_angular_momentums = match max_angular_momentum {
+            AngularMomentum::UnsupportedAngularMomentum => 0,
+            _ => max_angular_momentum as usize + 1,
+        };
+        let mut contractions = Vec::with_capacity(num_angular_momentums);
+        contractions.resize_with(num_angular_momentums, || Vec::with_capacity(shells_per_l));
+        AtomicBasisSet {
+            contractions,
+            ..Self::new()
+        }
     }
 
     pub fn get_num_contracted_functions(&self) -> usize {
-        self.0
+        self.contractions
             .iter()
             .map(|angular_momentum| angular_momentum.len())
             .sum()
     }
 
     pub fn get_num_gaussian_primitives(&self) -> usize {
-        self.0
+        self.contractions
             .iter()
             .map(|seg_contractions| {
                 seg_contractions
@@ -28,28 +220,791 @@ impl AtomicBasisSet {
             .sum()
     }
 
+    /// Contraction at a flat index running over all angular momentums in the order of the
+    /// iterator, S contractions first. `None` past the last contraction.
+    pub fn get_contraction(
+        &self,
+        global_index: usize,
+    ) -> Option<(AngularMomentum, &SegmentedContraction)> {
+        let mut remaining = global_index;
+        for (angular_momentum_num, seg_contractions) in self.contractions.iter().enumerate() {
+            match seg_contractions.get(remaining) {
+                Some(seg_contraction) => {
+                    return Some((slot_angular_momentum(angular_momentum_num), seg_contraction))
+                }
+                None => remaining -= seg_contractions.len(),
+            }
+        }
+        None
+    }
+
+    /// Number of basis functions once every shell is expanded into its 2l+1 spherical components
+    pub fn get_num_spherical_functions(&self) -> usize {
+        self.into_iter()
+            .map(|(angular_momentum, _)| angular_momentum.num_spherical_functions())
+            .sum()
+    }
+
+    /// Number of basis functions once every shell is expanded into its (l+1)(l+2)/2 Cartesian
+    /// components
+    pub fn get_num_cartesian_functions(&self) -> usize {
+        self.into_iter()
+            .map(|(angular_momentum, _)| angular_momentum.num_cartesian_functions())
+            .sum()
+    }
+
+    /// Number of Gaussian primitives under each angular momentum holding contractions
+    pub fn primitives_per_angular_momentum(&self) -> Vec<(AngularMomentum, usize)> {
+        self.contractions
+            .iter()
+            .enumerate()
+            .filter(|(_, seg_contractions)| !seg_contractions.is_empty())
+            .map(|(angular_momentum_num, seg_contractions)| {
+                (
+                    slot_angular_momentum(angular_momentum_num),
+                    seg_contractions
+                        .iter()
+                        .map(|seg_contraction| seg_contraction.get_num_primitives())
+                        .sum(),
+                )
+            })
+            .collect()
+    }
+
+    /// Number of contractions for each number of primitives, e.g. to tell a heavily contracted
+    /// basis set from a nearly uncontracted one
+    pub fn contraction_length_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for (_, seg_contraction) in self {
+            *histogram
+                .entry(seg_contraction.get_num_primitives())
+                .or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// Number of spatial orbitals the basis set can describe, i.e. the number of spherical
+    /// basis functions. Each orbital holds at most two electrons.
+    pub fn nominal_orbital_capacity(&self) -> usize {
+        self.get_num_spherical_functions()
+    }
+
+    /// Breakdown of `nominal_orbital_capacity` under each angular momentum holding contractions
+    pub fn nominal_orbital_capacity_per_angular_momentum(&self) -> Vec<(AngularMomentum, usize)> {
+        self.contractions
+            .iter()
+            .enumerate()
+            .filter(|(_, seg_contractions)| !seg_contractions.is_empty())
+            .map(|(angular_momentum_num, seg_contractions)| {
+                let angular_momentum = slot_angular_momentum(angular_momentum_num);
+                (
+                    angular_momentum,
+                    seg_contractions.len() * angular_momentum.num_spherical_functions(),
+                )
+            })
+            .collect()
+    }
+
+    /// Counts of contracted functions and primitives, e.g. for logging
+    pub fn summary(&self) -> BasisSetSummary {
+        BasisSetSummary {
+            num_shells: self.get_num_contracted_functions(),
+            num_primitives: self.get_num_gaussian_primitives(),
+            highest_angular_momentum: self.get_highest_angular_momentum(),
+            shells_per_angular_momentum: self
+                .contractions
+                .iter()
+                .enumerate()
+                .filter(|(_, seg_contractions)| !seg_contractions.is_empty())
+                .map(|(angular_momentum_num, seg_contractions)| {
+                    (
+                        slot_angular_momentum(angular_momentum_num),
+                        seg_contractions.len(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    pub fn get_spherical(&self) -> SphericalOrCartesian {
+        self.spherical
+    }
+
+    pub fn set_spherical(&mut self, value: SphericalOrCartesian) -> &mut Self {
+        self.spherical = value;
+        self
+    }
+
+    /// Integer closing the declaration line of the Gaussian94 format, 0 for a basis set applying
+    /// to every atom of its type. Some programs give other values a different meaning, hence it
+    /// is kept so that writers can reproduce it.
+    pub fn get_declaration_flag(&self) -> i32 {
+        self.declaration_flag
+    }
+
+    pub fn set_declaration_flag(&mut self, value: i32) -> &mut Self {
+        self.declaration_flag = value;
+        self
+    }
+
+    /// Number of basis functions with every shell expanded into the spherical or Cartesian
+    /// components the basis set is meant for
+    pub fn get_num_basis_functions(&self) -> usize {
+        match self.spherical {
+            SphericalOrCartesian::Spherical => self.get_num_spherical_functions(),
+            SphericalOrCartesian::Cartesian => self.get_num_cartesian_functions(),
+        }
+    }
+
+    /// Slots of the angular momentums up to the highest one holding a contraction
+    fn occupied_contractions(&self) -> &[Vec<SegmentedContraction>] {
+        let len = self
+            .contractions
+            .iter()
+            .rposition(|seg_contractions| !seg_contractions.is_empty())
+            .map_or(0, |index| index + 1);
+        &self.contractions[..len]
+    }
+
     pub fn get_highest_angular_momentum(&self) -> AngularMomentum {
-        let len = self.0.len();
+        let len = self.occupied_contractions().len();
 
         if len == 0 {
             AngularMomentum::UnsupportedAngularMomentum
         } else {
-            AngularMomentum::from(len - 1)
+            slot_angular_momentum(len - 1)
         }
     }
 
+    /// Adds the contraction under the angular momentum. `UnsupportedAngularMomentum` has no
+    /// slot and is refused.
     pub fn add_segmented_contraction(
         &mut self,
         angular_momentum: AngularMomentum,
         segmented_contraction: SegmentedContraction,
-    ) -> &mut Self {
+    ) -> Result<&mut Self, BasisSetValidationError> {
+        if angular_momentum == AngularMomentum::UnsupportedAngularMomentum {
+            return Err(BasisSetValidationError::UnsupportedAngularMomentum);
+        }
         let angular_momentum_num = angular_momentum as usize;
-        while self.0.len() <= angular_momentum_num {
-            self.0.push(vec![]);
+        while self.contractions.len() <= angular_momentum_num {
+            self.contractions.push(vec![]);
+        }
+        self.contractions[angular_momentum_num].push(segmented_contraction);
+        Ok(self)
+    }
+
+    /// Checks that every contraction has primitives, every exponental is positive and every
+    /// angular momentum is supported, reporting the first violation found
+    pub fn validate(&self) -> Result<(), BasisSetValidationError> {
+        for (angular_momentum_num, seg_contractions) in self.contractions.iter().enumerate() {
+            if seg_contractions.is_empty() {
+                continue;
+            }
+            let angular_momentum = AngularMomentum::try_from(angular_momentum_num)
+                .map_err(|_| BasisSetValidationError::UnsupportedAngularMomentum)?;
+            for (index, seg_contraction) in seg_contractions.iter().enumerate() {
+                if seg_contraction.get_num_primitives() == 0 {
+                    return Err(BasisSetValidationError::EmptyContraction {
+                        angular_momentum,
+                        index,
+                    });
+                }
+                if let Some(primitive) = seg_contraction
+                    .iter()
+                    .find(|primitive| primitive.exponental() <= 0.0)
+                {
+                    return Err(BasisSetValidationError::NonPositiveExponental {
+                        angular_momentum,
+                        index,
+                        exponental: primitive.exponental(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a segmented contraction built from matching coefficients and exponentals
+    pub fn add_shell(
+        &mut self,
+        angular_momentum: AngularMomentum,
+        coefficients: &[f64],
+        exponentals: &[f64],
+    ) -> Result<&mut Self, BasisSetValidationError> {
+        if angular_momentum == AngularMomentum::UnsupportedAngularMomentum {
+            return Err(BasisSetValidationError::UnsupportedAngularMomentum);
+        }
+        if coefficients.len() != exponentals.len() {
+            return Err(BasisSetValidationError::MismatchedLengths {
+                num_coefficients: coefficients.len(),
+                num_exponentals: exponentals.len(),
+            });
+        }
+
+        let mut segmented_contraction = SegmentedContraction::new();
+        for (coefficient, exponental) in coefficients.iter().zip(exponentals) {
+            segmented_contraction.add(*coefficient, *exponental);
+        }
+        self.add_segmented_contraction(angular_momentum, segmented_contraction)
+    }
+
+    /// Adds a shell given as rows of an exponental followed by coefficients, the way basis set
+    /// files list primitives. With several angular momentums (e.g. SP) each one takes its own
+    /// coefficient column; with a single one every coefficient column is a contracted function
+    /// of a general contraction. Every row needs the same number of columns, and at least one
+    /// coefficient.
+    pub fn add_combined_shell(
+        &mut self,
+        angular_momentums: &[AngularMomentum],
+        rows: &[Vec<f64>],
+    ) -> Result<&mut Self, BasisSetValidationError> {
+        if angular_momentums.is_empty()
+            || angular_momentums.contains(&AngularMomentum::UnsupportedAngularMomentum)
+        {
+            return Err(BasisSetValidationError::UnsupportedAngularMomentum);
+        }
+        let num_columns = match angular_momentums {
+            [_] => rows.first().map_or(2, |row| row.len()),
+            _ => angular_momentums.len() + 1,
+        };
+        if let Some(row) = rows
+            .iter()
+            .position(|row| row.len() != num_columns || row.len() < 2)
+        {
+            return Err(BasisSetValidationError::BadRowLength {
+                row,
+                num_columns: rows[row].len(),
+            });
+        }
+
+        let exponentals: Vec<f64> = rows.iter().map(|row| row[0]).collect();
+
+        let mut shell: Vec<(AngularMomentum, SegmentedContraction)> = vec![];
+        if let [angular_momentum] = angular_momentums {
+            let mut general_contraction = GeneralContraction::new(exponentals);
+            for column in 1..num_columns {
+                general_contraction
+                    .add_contracted_function(rows.iter().map(|row| row[column]).collect())?;
+            }
+            for segmented_contraction in general_contraction.to_segmented_contractions() {
+                shell.push((*angular_momentum, segmented_contraction));
+            }
+        } else {
+            for (index, angular_momentum) in angular_momentums.iter().enumerate() {
+                let mut segmented_contraction = SegmentedContraction::new();
+                for row in rows {
+                    segmented_contraction.add(row[index + 1], row[0]);
+                }
+                shell.push((*angular_momentum, segmented_contraction));
+            }
+        }
+
+        // Remember the contractions came from one shell so writers can combine them again
+        let shell_group = (shell.len() > 1).then(|| self.next_shell_group());
+        for (angular_momentum, mut segmented_contraction) in shell {
+            segmented_contraction.set_shell_group(shell_group);
+            self.add_segmented_contraction(angular_momentum, segmented_contraction)?;
+        }
+        Ok(self)
+    }
+
+    fn next_shell_group(&self) -> usize {
+        self.into_iter()
+            .filter_map(|(_, seg_contraction)| seg_contraction.shell_group())
+            .max()
+            .map_or(0, |shell_group| shell_group + 1)
+    }
+
+    /// Contractions grouped back into the shells they were read from, in iteration order. The
+    /// contractions of a shell group are only kept together while they share their exponentals,
+    /// e.g. the S and P parts of an SP shell, or the contracted functions of a general
+    /// contraction.
+    pub fn combined_shells(&self) -> Vec<(Vec<AngularMomentum>, Vec<&SegmentedContraction>)> {
+        let mut shells: Vec<(Vec<AngularMomentum>, Vec<&SegmentedContraction>)> = vec![];
+        // Shell group and the position of its shell in shells
+        let mut group_positions: Vec<(usize, usize)> = vec![];
+
+        for (angular_momentum, seg_contraction) in self {
+            if let Some(shell_group) = seg_contraction.shell_group() {
+                match group_positions
+                    .iter()
+                    .find(|(group, _)| *group == shell_group)
+                {
+                    Some((_, position)) => {
+                        let (angular_momentums, seg_contractions) = &mut shells[*position];
+                        if seg_contractions[0].shares_exponents(seg_contraction) {
+                            angular_momentums.push(angular_momentum);
+                            seg_contractions.push(seg_contraction);
+                            continue;
+                        }
+                    }
+                    None => group_positions.push((shell_group, shells.len())),
+                }
+            }
+            shells.push((vec![angular_momentum], vec![seg_contraction]));
+        }
+        shells
+    }
+
+    /// Puts the contractions of an angular momentum sharing their exponentals, within a
+    /// relative tolerance, in one shell group, so they are written as a single general
+    /// contraction. This undoes splitting a general contraction into separate shells.
+    /// Contractions grouped with another angular momentum, e.g. the parts of an SP shell, are
+    /// left as they are.
+    pub fn compact(&mut self) -> &mut Self {
+        let mut group_angular_momentums: Vec<(usize, AngularMomentum)> = vec![];
+        for (angular_momentum, seg_contraction) in &*self {
+            if let Some(shell_group) = seg_contraction.shell_group() {
+                group_angular_momentums.push((shell_group, angular_momentum));
+            }
+        }
+        let spans_angular_momentums = |seg_contraction: &SegmentedContraction| {
+            seg_contraction.shell_group().is_some_and(|shell_group| {
+                let mut angular_momentums = group_angular_momentums
+                    .iter()
+                    .filter(|(group, _)| *group == shell_group)
+                    .map(|(_, angular_momentum)| *angular_momentum);
+                let first = angular_momentums.next();
+                angular_momentums.any(|angular_momentum| Some(angular_momentum) != first)
+            })
+        };
+        let same_exponents = |a: &SegmentedContraction, b: &SegmentedContraction| {
+            a.get_num_primitives() == b.get_num_primitives()
+                && a.exponents()
+                    .zip(b.exponents())
+                    .all(|(a, b)| relative_eq(a, b, EXPONENTAL_RELATIVE_TOLERANCE))
+        };
+
+        for angular_momentum_num in 0..self.contractions.len() {
+            let seg_contractions = &self.contractions[angular_momentum_num];
+            let mut grouped = vec![false; seg_contractions.len()];
+            let mut clusters: Vec<Vec<usize>> = vec![];
+            for (i, a) in seg_contractions.iter().enumerate() {
+                if grouped[i] || spans_angular_momentums(a) {
+                    continue;
+                }
+                let cluster: Vec<usize> = (i..seg_contractions.len())
+                    .filter(|j| {
+                        !grouped[*j]
+                            && !spans_angular_momentums(&seg_contractions[*j])
+                            && same_exponents(a, &seg_contractions[*j])
+                    })
+                    .collect();
+                for j in cluster.iter() {
+                    grouped[*j] = true;
+                }
+                if cluster.len() > 1 {
+                    clusters.push(cluster);
+                }
+            }
+
+            for cluster in clusters {
+                let shell_group = cluster
+                    .iter()
+                    .find_map(|index| self.contractions[angular_momentum_num][*index].shell_group())
+                    .unwrap_or_else(|| self.next_shell_group());
+                let exponentals: Vec<f64> = self.contractions[angular_momentum_num][cluster[0]]
+                    .exponents()
+                    .collect();
+                for index in cluster {
+                    // Exponentals within the tolerance are made equal for writers to combine
+                    let seg_contraction = &self.contractions[angular_momentum_num][index];
+                    let mut compacted = SegmentedContraction::with_capacity(exponentals.len());
+                    for (coefficient, exponental) in
+                        seg_contraction.coefficients().zip(exponentals.iter())
+                    {
+                        compacted.add(coefficient, *exponental);
+                    }
+                    compacted.set_shell_group(Some(shell_group));
+                    self.contractions[angular_momentum_num][index] = compacted;
+                }
+            }
+        }
+        self
+    }
+
+    /// Appends the segmented contractions of another basis set, e.g. diffuse functions from
+    /// another file, keeping them grouped under their angular momentum
+    pub fn merge(&mut self, other: AtomicBasisSet) -> &mut Self {
+        // Shell groups of the other basis set must not collide with the ones already here
+        let shell_group_offset = self.next_shell_group();
+        for (angular_momentum_num, mut seg_contractions) in
+            other.contractions.into_iter().enumerate()
+        {
+            while self.contractions.len() <= angular_momentum_num {
+                self.contractions.push(vec![]);
+            }
+            for seg_contraction in seg_contractions.iter_mut() {
+                let shell_group = seg_contraction
+                    .shell_group()
+                    .map(|shell_group| shell_group + shell_group_offset);
+                seg_contraction.set_shell_group(shell_group);
+            }
+            self.contractions[angular_momentum_num].append(&mut seg_contractions);
+        }
+        self
+    }
+
+    /// Contractions added, removed or modified going from this basis set to the other one.
+    /// Primitives are compared within the relative tolerance.
+    pub fn diff(&self, other: &AtomicBasisSet, tol: f64) -> BasisSetDiff {
+        let mut diff = BasisSetDiff::default();
+        let num_angular_momentums = self.contractions.len().max(other.contractions.len());
+        for angular_momentum_num in 0..num_angular_momentums {
+            let angular_momentum = slot_angular_momentum(angular_momentum_num);
+            let seg_contractions = self
+                .contractions
+                .get(angular_momentum_num)
+                .map_or(&[][..], |seg_contractions| seg_contractions.as_slice());
+            let other_seg_contractions = other
+                .contractions
+                .get(angular_momentum_num)
+                .map_or(&[][..], |seg_contractions| seg_contractions.as_slice());
+
+            for index in 0..seg_contractions.len().max(other_seg_contractions.len()) {
+                match (
+                    seg_contractions.get(index),
+                    other_seg_contractions.get(index),
+                ) {
+                    (Some(seg_contraction), Some(other_seg_contraction)) => {
+                        let num_primitives = seg_contraction
+                            .get_num_primitives()
+                            .max(other_seg_contraction.get_num_primitives());
+                        let changed: Vec<usize> = (0..num_primitives)
+                            .filter(|primitive_index| {
+                                match (
+                                    seg_contraction.get(*primitive_index),
+                                    other_seg_contraction.get(*primitive_index),
+                                ) {
+                                    (Some(a), Some(b)) => !a.approx_eq(b, tol),
+                                    _ => true,
+                                }
+                            })
+                            .collect();
+                        if !changed.is_empty() {
+                            diff.modified.push((angular_momentum, index, changed));
+                        }
+                    }
+                    (Some(_), None) => diff.removed.push((angular_momentum, index)),
+                    (None, Some(_)) => diff.added.push((angular_momentum, index)),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        diff
+    }
+
+    /// Applies the primitive and then the contraction normalization to every segmented
+    /// contraction, so each contracted function has a self-overlap of 1
+    pub fn normalize(&mut self) -> &mut Self {
+        for (angular_momentum_num, seg_contractions) in self.contractions.iter_mut().enumerate() {
+            let angular_momentum = slot_angular_momentum(angular_momentum_num);
+            for seg_contraction in seg_contractions.iter_mut() {
+                seg_contraction
+                    .normalize_primitives(angular_momentum)
+                    .normalize_contraction(angular_momentum);
+            }
+        }
+        self
+    }
+
+    /// Multiplies the exponentals of every segmented contraction by the factor
+    pub fn scale_exponents(&mut self, factor: f64) -> &mut Self {
+        for seg_contractions in self.contractions.iter_mut() {
+            for seg_contraction in seg_contractions.iter_mut() {
+                seg_contraction.scale_exponents(factor);
+            }
         }
-        self.0[angular_momentum_num].push(segmented_contraction);
         self
     }
+
+    /// Copy of the basis set with `f` applied to every primitive, along with its angular
+    /// momentum. The contractions keep their order and shell grouping.
+    pub fn map_primitives<F: FnMut(AngularMomentum, GaussianPrimitive) -> GaussianPrimitive>(
+        &self,
+        mut f: F,
+    ) -> AtomicBasisSet {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .set_spherical(self.spherical)
+            .set_declaration_flag(self.declaration_flag);
+        for (angular_momentum_num, seg_contractions) in self.contractions.iter().enumerate() {
+            let angular_momentum = slot_angular_momentum(angular_momentum_num);
+            basis_set.contractions.push(
+                seg_contractions
+                    .iter()
+                    .map(|seg_contraction| {
+                        let mut mapped = SegmentedContraction::new();
+                        mapped.set_shell_group(seg_contraction.shell_group());
+                        for primitive in seg_contraction {
+                            mapped.add_primitive(f(angular_momentum, *primitive));
+                        }
+                        mapped
+                    })
+                    .collect(),
+            );
+        }
+        basis_set
+    }
+
+    /// Pairs of contraction indices within the angular momentum whose overlap exceeds the
+    /// threshold in magnitude, once both are normalized. Such nearly identical contractions make
+    /// the basis set close to linearly dependent.
+    pub fn linear_dependence_candidates(
+        &self,
+        angular_momentum: AngularMomentum,
+        overlap_threshold: f64,
+    ) -> Vec<(usize, usize)> {
+        let normalized: Vec<SegmentedContraction> = self
+            .iter_in_order(&[angular_momentum])
+            .map(|(_, seg_contraction)| {
+                let mut seg_contraction = seg_contraction.clone();
+                seg_contraction
+                    .normalize_primitives(angular_momentum)
+                    .normalize_contraction(angular_momentum);
+                seg_contraction
+            })
+            .collect();
+
+        let mut candidates = vec![];
+        for (i, a) in normalized.iter().enumerate() {
+            for (j, b) in normalized.iter().enumerate().skip(i + 1) {
+                if overlap_same_center(a, b, angular_momentum).abs() > overlap_threshold {
+                    candidates.push((i, j));
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Drops the primitives with an exponental below `min_exponent`, along with the contractions
+    /// left without any primitive
+    pub fn remove_diffuse(&mut self, min_exponent: f64) -> &mut Self {
+        for seg_contractions in self.contractions.iter_mut() {
+            for seg_contraction in seg_contractions.iter_mut() {
+                seg_contraction.remove_diffuse(min_exponent);
+            }
+            seg_contractions.retain(|seg_contraction| seg_contraction.get_num_primitives() > 0);
+        }
+        // Keep the highest angular momentum in line with the remaining contractions
+        while self
+            .contractions
+            .last()
+            .is_some_and(|seg_contractions| seg_contractions.is_empty())
+        {
+            self.contractions.pop();
+        }
+        self
+    }
+
+    /// Iterates like `into_iter`, along with the 0-based index of each contraction within its
+    /// angular momentum
+    pub fn iter_indexed(
+        &self,
+    ) -> impl Iterator<Item = (AngularMomentum, usize, &SegmentedContraction)> {
+        self.contractions
+            .iter()
+            .enumerate()
+            .flat_map(|(angular_momentum_num, seg_contractions)| {
+                seg_contractions
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, seg_contraction)| {
+                        (
+                            slot_angular_momentum(angular_momentum_num),
+                            index,
+                            seg_contraction,
+                        )
+                    })
+            })
+    }
+
+    /// Radial part of every contracted function at each of the radii, along with its angular
+    /// momentum and index within that angular momentum, e.g. to plot the basis set
+    pub fn sample_radial(&self, radii: &[f64]) -> Vec<(AngularMomentum, usize, Vec<f64>)> {
+        self.iter_indexed()
+            .map(|(angular_momentum, index, seg_contraction)| {
+                let values = radii
+                    .iter()
+                    .map(|r| seg_contraction.evaluate_radial(angular_momentum, *r))
+                    .collect();
+                (angular_momentum, index, values)
+            })
+            .collect()
+    }
+
+    /// Iterates like `into_iter`, but visiting the angular momentums in the given order, e.g.
+    /// for formats expecting a specific shell ordering. Angular momentums without contractions
+    /// are skipped.
+    pub fn iter_in_order<'a>(
+        &'a self,
+        order: &'a [AngularMomentum],
+    ) -> impl Iterator<Item = (AngularMomentum, &'a SegmentedContraction)> + 'a {
+        order.iter().flat_map(move |angular_momentum| {
+            let seg_contractions: &[SegmentedContraction] = match *angular_momentum {
+                AngularMomentum::UnsupportedAngularMomentum => &[],
+                _ => self
+                    .contractions
+                    .get(*angular_momentum as usize)
+                    .map_or(&[], |seg_contractions| seg_contractions.as_slice()),
+            };
+            seg_contractions
+                .iter()
+                .map(move |seg_contraction| (*angular_momentum, seg_contraction))
+        })
+    }
+
+    /// Consumes the basis set, yielding owned contractions in the order of `into_iter`, e.g. to
+    /// transform them and collect into a new basis set
+    pub fn into_contractions(
+        self,
+    ) -> impl Iterator<Item = (AngularMomentum, SegmentedContraction)> {
+        self.contractions.into_iter().enumerate().flat_map(
+            |(angular_momentum_num, seg_contractions)| {
+                let angular_momentum = slot_angular_momentum(angular_momentum_num);
+                seg_contractions
+                    .into_iter()
+                    .map(move |seg_contraction| (angular_momentum, seg_contraction))
+            },
+        )
+    }
+
+    /// New basis set holding only the contractions of the angular momentum
+    pub fn extract_angular_momentum(&self, angular_momentum: AngularMomentum) -> AtomicBasisSet {
+        let mut basis_set: AtomicBasisSet = self
+            .iter_in_order(&[angular_momentum])
+            .map(|(angular_momentum, seg_contraction)| (angular_momentum, seg_contraction.clone()))
+            .collect();
+        basis_set
+            .set_spherical(self.spherical)
+            .set_declaration_flag(self.declaration_flag);
+        basis_set
+    }
+
+    /// Uncontracted version of the basis set, each distinct primitive exponental of an angular
+    /// momentum becomes its own contracted function with coefficient 1
+    pub fn decontract(&self) -> AtomicBasisSet {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .set_spherical(self.spherical)
+            .set_declaration_flag(self.declaration_flag);
+        for seg_contractions in self.contractions.iter() {
+            let mut exponentals: Vec<f64> = vec![];
+            for primitive in seg_contractions.iter().flat_map(|c| c.iter()) {
+                if !exponentals.contains(&primitive.exponental()) {
+                    exponentals.push(primitive.exponental());
+                }
+            }
+            basis_set.contractions.push(
+                exponentals
+                    .into_iter()
+                    .map(|exponental| {
+                        let mut seg_contraction = SegmentedContraction::new();
+                        seg_contraction.add(1.0, exponental);
+                        seg_contraction
+                    })
+                    .collect(),
+            );
+        }
+        basis_set
+    }
+
+    /// Flat copy of every contracted function as its angular momentum, coefficients and
+    /// exponentals, in the order of `into_iter`. This is the shell layout expected by most
+    /// integral libraries.
+    pub fn to_shell_tuples(&self) -> Vec<(AngularMomentum, Vec<f64>, Vec<f64>)> {
+        self.into_iter()
+            .map(|(angular_momentum, seg_contraction)| {
+                (
+                    angular_momentum,
+                    seg_contraction.coefficients().collect(),
+                    seg_contraction.exponents().collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Contractions of any angular momentum with at least one exponental within `[lo, hi]`
+    pub fn contractions_in_exponent_range(
+        &self,
+        lo: f64,
+        hi: f64,
+    ) -> Vec<(AngularMomentum, &SegmentedContraction)> {
+        self.into_iter()
+            .filter(|(_, seg_contraction)| {
+                seg_contraction
+                    .exponents()
+                    .any(|exponental| (lo..=hi).contains(&exponental))
+            })
+            .collect()
+    }
+
+    /// Approximate number of bytes the basis set allocates on the heap, from the capacities of
+    /// its nested vectors
+    pub fn memory_footprint(&self) -> usize {
+        self.contractions.capacity() * std::mem::size_of::<Vec<SegmentedContraction>>()
+            + self
+                .contractions
+                .iter()
+                .map(|seg_contractions| {
+                    seg_contractions.capacity() * std::mem::size_of::<SegmentedContraction>()
+                        + seg_contractions
+                            .iter()
+                            .map(|seg_contraction| seg_contraction.heap_bytes())
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+    }
+
+    /// Hash of the contents, stable across runs and platforms, e.g. to key a cache. The angular
+    /// momentum, number of primitives and the bits of every coefficient and exponental are
+    /// hashed with FNV-1a, in the order of `into_iter` then primitive order.
+    pub fn content_hash(&self) -> u64 {
+        self.into_iter().fold(
+            FNV_OFFSET_BASIS,
+            |hash, (angular_momentum, seg_contraction)| {
+                let hash = fnv1a(hash, angular_momentum as u64);
+                let hash = fnv1a(hash, seg_contraction.get_num_primitives() as u64);
+                seg_contraction.iter().fold(hash, |hash, primitive| {
+                    let hash = fnv1a(hash, primitive.coefficient().to_bits());
+                    fnv1a(hash, primitive.exponental().to_bits())
+                })
+            },
+        )
+    }
+
+    fn exponentals(&self) -> impl Iterator<Item = f64> + '_ {
+        self.into_iter()
+            .flat_map(|(_, seg_contraction)| seg_contraction.iter())
+            .map(|primitive| primitive.exponental())
+    }
+
+    /// Exponental of the tightest primitive, `None` for an empty basis set
+    pub fn max_exponent(&self) -> Option<f64> {
+        self.exponentals().reduce(f64::max)
+    }
+
+    /// Exponental of the most diffuse primitive, `None` for an empty basis set
+    pub fn min_exponent(&self) -> Option<f64> {
+        self.exponentals().reduce(f64::min)
+    }
+
+    /// Distinct exponentals of an angular momentum in descending order. Exponentals within a
+    /// relative tolerance of each other are reported once.
+    pub fn unique_exponents(&self, angular_momentum: AngularMomentum) -> Vec<f64> {
+        let mut exponentals: Vec<f64> = match self.contractions.get(angular_momentum as usize) {
+            None => return vec![],
+            Some(seg_contractions) => seg_contractions
+                .iter()
+                .flat_map(|c| c.iter())
+                .map(|primitive| primitive.exponental())
+                .collect(),
+        };
+        exponentals.sort_by(|a, b| b.total_cmp(a));
+        exponentals.dedup_by(|a, b| relative_eq(*a, *b, EXPONENTAL_RELATIVE_TOLERANCE));
+        exponentals
+    }
 }
 
 pub struct SegmentedContractionIntoIterator<'a> {
@@ -74,11 +1029,11 @@ impl<'a> Iterator for SegmentedContractionIntoIterator<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         let mut result: Option<Self::Item> = None;
 
-        while self.angular_momentum_index < self.ao_basis_set.0.len() {
-            let scgtos = &self.ao_basis_set.0[self.angular_momentum_index];
+        while self.angular_momentum_index < self.ao_basis_set.contractions.len() {
+            let scgtos = &self.ao_basis_set.contractions[self.angular_momentum_index];
             if self.segmented_contraction_index < scgtos.len() {
                 result = Some((
-                    AngularMomentum::from(self.angular_momentum_index),
+                    slot_angular_momentum(self.angular_momentum_index),
                     &scgtos[self.segmented_contraction_index],
                 ));
                 self.segmented_contraction_index += 1;
@@ -99,6 +1054,664 @@ impl<'a> IntoIterator for &'a AtomicBasisSet {
     type IntoIter = SegmentedContractionIntoIterator<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        SegmentedContractionIntoIterator::new(&self)
+        SegmentedContractionIntoIterator::new(self)
+    }
+}
+
+/// Adds the contractions in order, e.g. to rebuild a basis set from its filtered iterator.
+/// Pairs under `UnsupportedAngularMomentum` have no slot and are skipped.
+impl FromIterator<(AngularMomentum, SegmentedContraction)> for AtomicBasisSet {
+    fn from_iter<T: IntoIterator<Item = (AngularMomentum, SegmentedContraction)>>(iter: T) -> Self {
+        let mut basis_set = AtomicBasisSet::new();
+        for (angular_momentum, segmented_contraction) in iter {
+            // The sentinel is the only angular momentum refused
+            let _ = basis_set.add_segmented_contraction(angular_momentum, segmented_contraction);
+        }
+        basis_set
+    }
+}
+
+#[cfg(test)]
+impl AtomicBasisSet {
+    /// Basis set holding an S contraction past the highest supported angular momentum, a state
+    /// only deserialized data can reach
+    pub(crate) fn with_unsupported_slot() -> Self {
+        let mut contraction = SegmentedContraction::new();
+        contraction.add(1.0, 0.5);
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .contractions
+            .resize(AngularMomentum::all().len() + 1, vec![]);
+        basis_set.contractions.last_mut().unwrap().push(contraction);
+        basis_set
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use crate::details::{
+        angular_momentum::AngularMomentum,
+        basis_set_assignment::BasisSetAssignmentType,
+        gaussian_exp::{GaussianPrimitive, SegmentedContraction},
+        library::BasisSetLibrary,
+    };
+
+    use super::{AtomicBasisSet, BasisSetValidationError, SphericalOrCartesian};
+
+    /// Two S contractions, 3.0/0.5 and 0.1, and a P contraction of 0.8
+    pub(crate) fn sample_basis_set() -> AtomicBasisSet {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_shell(AngularMomentum::S, &[0.4, 0.6], &[3.0, 0.5])
+            .unwrap()
+            .add_shell(AngularMomentum::S, &[1.0], &[0.1])
+            .unwrap()
+            .add_shell(AngularMomentum::P, &[1.0], &[0.8])
+            .unwrap();
+        basis_set
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut s_contraction = SegmentedContraction::new();
+        s_contraction.add(0.4, 3.0).add(0.6, 0.5);
+        let mut s_basis_set = AtomicBasisSet::new();
+        s_basis_set
+            .add_segmented_contraction(AngularMomentum::S, s_contraction)
+            .unwrap();
+
+        let mut p_contraction = SegmentedContraction::new();
+        p_contraction.add(1.0, 0.8);
+        let mut p_basis_set = AtomicBasisSet::new();
+        p_basis_set
+            .add_segmented_contraction(AngularMomentum::P, p_contraction)
+            .unwrap();
+
+        s_basis_set.merge(p_basis_set);
+        assert_eq!(s_basis_set.get_num_contracted_functions(), 2);
+        assert_eq!(s_basis_set.get_num_gaussian_primitives(), 3);
+        assert_eq!(
+            s_basis_set.get_highest_angular_momentum(),
+            AngularMomentum::P
+        );
+
+        let angular_momentums: Vec<AngularMomentum> =
+            s_basis_set.into_iter().map(|(am, _)| am).collect();
+        assert_eq!(
+            angular_momentums,
+            vec![AngularMomentum::S, AngularMomentum::P]
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let basis_set = sample_basis_set();
+        assert!(basis_set.diff(&basis_set.clone(), 1e-10).is_empty());
+
+        // Only the second exponental of the first S contraction is scaled
+        let mut num_primitives = 0;
+        let scaled = basis_set.map_primitives(|_, primitive| {
+            num_primitives += 1;
+            match num_primitives {
+                2 => GaussianPrimitive::new(primitive.coefficient(), primitive.exponental() * 1.1),
+                _ => primitive,
+            }
+        });
+        let diff = basis_set.diff(&scaled, 1e-10);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.modified, vec![(AngularMomentum::S, 0, vec![1])]);
+        assert!(basis_set.diff(&scaled, 0.2).is_empty());
+
+        let mut extended = basis_set.clone();
+        extended
+            .add_shell(AngularMomentum::D, &[1.0], &[0.6])
+            .unwrap();
+        let diff = basis_set.diff(&extended, 1e-10);
+        assert_eq!(diff.added, vec![(AngularMomentum::D, 0)]);
+        assert!(diff.modified.is_empty());
+        let diff = extended.diff(&basis_set, 1e-10);
+        assert_eq!(diff.removed, vec![(AngularMomentum::D, 0)]);
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let mut basis_set = AtomicBasisSet::with_capacity(AngularMomentum::D, 4);
+        assert_eq!(
+            basis_set.get_highest_angular_momentum(),
+            AngularMomentum::UnsupportedAngularMomentum
+        );
+        assert_eq!(basis_set.get_num_contracted_functions(), 0);
+        assert_eq!(basis_set, AtomicBasisSet::new());
+
+        // Only S shells, the reserved P and D slots stay empty
+        let mut s_basis_set = AtomicBasisSet::with_capacity(AngularMomentum::D, 4);
+        s_basis_set
+            .add_shell(AngularMomentum::S, &[1.0], &[0.5])
+            .unwrap();
+        let mut s_expected = AtomicBasisSet::new();
+        s_expected
+            .add_shell(AngularMomentum::S, &[1.0], &[0.5])
+            .unwrap();
+        assert_eq!(s_basis_set, s_expected);
+        assert_eq!(
+            s_basis_set.get_highest_angular_momentum(),
+            AngularMomentum::S
+        );
+
+        let mut expected = AtomicBasisSet::new();
+        for angular_momentum in [AngularMomentum::S, AngularMomentum::P, AngularMomentum::D] {
+            for index in 0..4 {
+                let mut seg_contraction = SegmentedContraction::with_capacity(1);
+                seg_contraction.add(1.0, 0.5_f64.powi(index));
+                basis_set
+                    .add_segmented_contraction(angular_momentum, seg_contraction.clone())
+                    .unwrap();
+                expected
+                    .add_segmented_contraction(angular_momentum, seg_contraction)
+                    .unwrap();
+            }
+        }
+        assert_eq!(basis_set, expected);
+        assert_eq!(
+            AtomicBasisSet::with_capacity(AngularMomentum::UnsupportedAngularMomentum, 4),
+            AtomicBasisSet::new()
+        );
+    }
+
+    #[test]
+    fn test_scale_exponents() {
+        let mut s_contraction = SegmentedContraction::new();
+        s_contraction.add(0.4, 3.0).add(0.6, 0.5);
+        let mut p_contraction = SegmentedContraction::new();
+        p_contraction.add(1.0, 0.8);
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_segmented_contraction(AngularMomentum::S, s_contraction)
+            .unwrap()
+            .add_segmented_contraction(AngularMomentum::P, p_contraction)
+            .unwrap();
+
+        basis_set.scale_exponents(2.0);
+        let primitives: Vec<(f64, f64)> = basis_set
+            .into_iter()
+            .flat_map(|(_, contraction)| contraction.iter())
+            .map(|primitive| (primitive.coefficient(), primitive.exponental()))
+            .collect();
+        assert_eq!(primitives.len(), 3);
+        for ((coefficient, exponental), (expected_coefficient, expected_exponental)) in primitives
+            .into_iter()
+            .zip([(0.4, 6.0), (0.6, 1.0), (1.0, 1.6)])
+        {
+            assert_abs_diff_eq!(coefficient, expected_coefficient);
+            assert_abs_diff_eq!(exponental, expected_exponental);
+        }
+    }
+
+    #[test]
+    fn test_map_primitives() {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_combined_shell(
+                &[AngularMomentum::S, AngularMomentum::P],
+                &[vec![5.03319, 0.4, 0.2], vec![0.51234, 0.6, 0.8]],
+            )
+            .unwrap();
+        basis_set
+            .add_shell(AngularMomentum::D, &[1.0], &[0.80049])
+            .unwrap();
+
+        let rounded = basis_set.map_primitives(|_, primitive| {
+            GaussianPrimitive::new(
+                primitive.coefficient(),
+                (primitive.exponental() * 1000.0).round() / 1000.0,
+            )
+        });
+        assert_eq!(rounded.get_num_contracted_functions(), 3);
+        assert_eq!(rounded.get_num_gaussian_primitives(), 5);
+        assert_eq!(rounded.combined_shells().len(), 2);
+        let primitives: Vec<(AngularMomentum, f64, f64)> = rounded
+            .into_iter()
+            .flat_map(|(angular_momentum, contraction)| {
+                contraction.iter().map(move |primitive| {
+                    (
+                        angular_momentum,
+                        primitive.coefficient(),
+                        primitive.exponental(),
+                    )
+                })
+            })
+            .collect();
+        assert_eq!(
+            primitives,
+            vec![
+                (AngularMomentum::S, 0.4, 5.033),
+                (AngularMomentum::S, 0.6, 0.512),
+                (AngularMomentum::P, 0.2, 5.033),
+                (AngularMomentum::P, 0.8, 0.512),
+                (AngularMomentum::D, 1.0, 0.8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_linear_dependence_candidates() {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_shell(AngularMomentum::S, &[0.4, 0.6], &[3.0, 0.5])
+            .unwrap()
+            .add_shell(AngularMomentum::S, &[1.0], &[0.05])
+            .unwrap()
+            .add_shell(AngularMomentum::S, &[0.41, 0.59], &[3.01, 0.5])
+            .unwrap()
+            .add_shell(AngularMomentum::P, &[1.0], &[0.8])
+            .unwrap();
+
+        assert_eq!(
+            basis_set.linear_dependence_candidates(AngularMomentum::S, 0.99),
+            vec![(0, 2)]
+        );
+        assert!(basis_set
+            .linear_dependence_candidates(AngularMomentum::P, 0.99)
+            .is_empty());
+        assert!(basis_set
+            .linear_dependence_candidates(AngularMomentum::D, 0.99)
+            .is_empty());
+        assert_eq!(
+            basis_set
+                .linear_dependence_candidates(AngularMomentum::S, 0.0)
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_add_shell() {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_shell(AngularMomentum::S, &[0.4, 0.6], &[3.0, 0.5])
+            .unwrap()
+            .add_shell(AngularMomentum::P, &[1.0], &[0.8])
+            .unwrap();
+        assert_eq!(basis_set.get_num_contracted_functions(), 2);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 3);
+
+        let (angular_momentum, contraction) = basis_set.into_iter().next().unwrap();
+        assert_eq!(angular_momentum, AngularMomentum::S);
+        assert_eq!(contraction.get_num_primitives(), 2);
+        assert_abs_diff_eq!(contraction.get(1).unwrap().coefficient(), 0.6);
+        assert_abs_diff_eq!(contraction.get(1).unwrap().exponental(), 0.5);
+
+        assert_eq!(
+            basis_set
+                .add_shell(AngularMomentum::S, &[0.4, 0.6], &[3.0])
+                .err(),
+            Some(BasisSetValidationError::MismatchedLengths {
+                num_coefficients: 2,
+                num_exponentals: 1
+            })
+        );
+        assert_eq!(basis_set.get_num_contracted_functions(), 2);
+    }
+
+    #[test]
+    fn test_add_combined_shell() {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_combined_shell(
+                &[AngularMomentum::S],
+                &[vec![3.0, 0.4, 0.1], vec![0.5, 0.6, 0.9]],
+            )
+            .unwrap();
+        assert_eq!(basis_set.get_num_contracted_functions(), 2);
+
+        for (angular_momentums, rows, expected) in [
+            (
+                vec![AngularMomentum::S],
+                vec![vec![1.0, 0.5, 0.3], vec![2.0, 0.4]],
+                BasisSetValidationError::BadRowLength {
+                    row: 1,
+                    num_columns: 2,
+                },
+            ),
+            (
+                vec![AngularMomentum::S],
+                vec![vec![1.0]],
+                BasisSetValidationError::BadRowLength {
+                    row: 0,
+                    num_columns: 1,
+                },
+            ),
+            (
+                vec![AngularMomentum::S, AngularMomentum::P],
+                vec![vec![1.0, 0.5, 0.3], vec![2.0, 0.4]],
+                BasisSetValidationError::BadRowLength {
+                    row: 1,
+                    num_columns: 2,
+                },
+            ),
+            (
+                vec![AngularMomentum::S, AngularMomentum::P],
+                vec![vec![1.0, 0.5, 0.3, 0.2]],
+                BasisSetValidationError::BadRowLength {
+                    row: 0,
+                    num_columns: 4,
+                },
+            ),
+            (
+                vec![AngularMomentum::UnsupportedAngularMomentum],
+                vec![vec![1.0, 0.5]],
+                BasisSetValidationError::UnsupportedAngularMomentum,
+            ),
+            (
+                vec![],
+                vec![vec![1.0, 0.5]],
+                BasisSetValidationError::UnsupportedAngularMomentum,
+            ),
+        ] {
+            assert_eq!(
+                basis_set
+                    .add_combined_shell(&angular_momentums, &rows)
+                    .err(),
+                Some(expected)
+            );
+        }
+        assert_eq!(basis_set.get_num_contracted_functions(), 2);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut basis_set = sample_basis_set();
+        assert_eq!(basis_set.validate(), Ok(()));
+
+        basis_set
+            .add_shell(AngularMomentum::P, &[0.5, 0.5], &[2.0, 0.0])
+            .unwrap();
+        assert_eq!(
+            basis_set.validate(),
+            Err(BasisSetValidationError::NonPositiveExponental {
+                angular_momentum: AngularMomentum::P,
+                index: 1,
+                exponental: 0.0
+            })
+        );
+
+        let mut empty_basis_set = AtomicBasisSet::new();
+        empty_basis_set
+            .add_segmented_contraction(AngularMomentum::D, SegmentedContraction::new())
+            .unwrap();
+        // The sentinel has no slot and is refused rather than growing the basis set
+        assert_eq!(
+            empty_basis_set
+                .add_segmented_contraction(
+                    AngularMomentum::UnsupportedAngularMomentum,
+                    SegmentedContraction::new()
+                )
+                .err(),
+            Some(BasisSetValidationError::UnsupportedAngularMomentum)
+        );
+        assert_eq!(empty_basis_set.get_num_contracted_functions(), 1);
+        assert_eq!(
+            empty_basis_set.get_highest_angular_momentum(),
+            AngularMomentum::D
+        );
+        assert_eq!(
+            empty_basis_set.validate(),
+            Err(BasisSetValidationError::EmptyContraction {
+                angular_momentum: AngularMomentum::D,
+                index: 0
+            })
+        );
+
+        assert_eq!(
+            AtomicBasisSet::with_unsupported_slot().validate(),
+            Err(BasisSetValidationError::UnsupportedAngularMomentum)
+        );
+    }
+
+    #[test]
+    fn test_num_functions() {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_shell(AngularMomentum::S, &[1.0], &[3.0])
+            .unwrap()
+            .add_shell(AngularMomentum::P, &[1.0], &[0.8])
+            .unwrap()
+            .add_shell(AngularMomentum::D, &[1.0], &[0.6])
+            .unwrap();
+        assert_eq!(basis_set.get_num_spherical_functions(), 9);
+        assert_eq!(basis_set.get_num_cartesian_functions(), 10);
+    }
+
+    #[test]
+    fn test_iter_indexed() {
+        let mut basis_set = sample_basis_set();
+        basis_set
+            .add_shell(AngularMomentum::P, &[1.0], &[0.2])
+            .unwrap();
+
+        let indices: Vec<(AngularMomentum, usize, usize)> = basis_set
+            .iter_indexed()
+            .map(|(am, index, contraction)| (am, index, contraction.get_num_primitives()))
+            .collect();
+        assert_eq!(
+            indices,
+            vec![
+                (AngularMomentum::S, 0, 2),
+                (AngularMomentum::S, 1, 1),
+                (AngularMomentum::P, 0, 1),
+                (AngularMomentum::P, 1, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_combined_shells() {
+        let sp_rows = vec![vec![3.0, 0.1, 0.2], vec![0.5, 0.9, 0.8]];
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_shell(AngularMomentum::S, &[1.0], &[10.0])
+            .unwrap()
+            .add_combined_shell(&[AngularMomentum::S, AngularMomentum::P], &sp_rows)
+            .unwrap();
+        let mut other = AtomicBasisSet::new();
+        other
+            .add_combined_shell(&[AngularMomentum::S, AngularMomentum::P], &sp_rows)
+            .unwrap();
+        basis_set.merge(other);
+
+        let shells: Vec<(Vec<AngularMomentum>, usize)> = basis_set
+            .combined_shells()
+            .into_iter()
+            .map(|(angular_momentums, contractions)| {
+                (angular_momentums, contractions[0].get_num_primitives())
+            })
+            .collect();
+        assert_eq!(
+            shells,
+            vec![
+                (vec![AngularMomentum::S], 1),
+                (vec![AngularMomentum::S, AngularMomentum::P], 2),
+                (vec![AngularMomentum::S, AngularMomentum::P], 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_shell(AngularMomentum::S, &[0.4, 0.6], &[3.0, 0.5])
+            .unwrap()
+            .add_combined_shell(
+                &[AngularMomentum::S, AngularMomentum::P],
+                &[vec![3.0, 0.1, 0.3], vec![0.5, 0.9, 0.7]],
+            )
+            .unwrap()
+            .add_shell(AngularMomentum::S, &[-0.2, 1.1], &[3.0, 0.5 + 1e-12])
+            .unwrap()
+            .add_shell(AngularMomentum::S, &[1.0], &[0.1])
+            .unwrap();
+        assert_eq!(basis_set.combined_shells().len(), 4);
+
+        basis_set.compact();
+        let shells = basis_set.combined_shells();
+        assert_eq!(shells.len(), 3);
+        assert_eq!(shells[0].0, vec![AngularMomentum::S, AngularMomentum::S]);
+        assert_eq!(
+            shells[0].1[1].coefficients().collect::<Vec<_>>(),
+            vec![-0.2, 1.1]
+        );
+        assert_eq!(
+            shells[0].1[1].exponents().collect::<Vec<_>>(),
+            vec![3.0, 0.5]
+        );
+        // The SP shell is kept
+        assert_eq!(shells[1].0, vec![AngularMomentum::S, AngularMomentum::P]);
+        assert_eq!(shells[2].0, vec![AngularMomentum::S]);
+        assert_eq!(basis_set.get_num_contracted_functions(), 5);
+
+        // Compacting again changes nothing
+        let compacted = basis_set.clone();
+        assert_eq!(*basis_set.compact(), compacted);
+    }
+
+    #[test]
+    fn test_memory_footprint() {
+        let mut basis_set = AtomicBasisSet::new();
+        assert_eq!(basis_set.memory_footprint(), 0);
+
+        basis_set
+            .add_shell(AngularMomentum::S, &[0.4, 0.6], &[3.0, 0.5])
+            .unwrap();
+        let footprint = basis_set.memory_footprint();
+        assert!(footprint >= 2 * std::mem::size_of::<GaussianPrimitive>());
+
+        basis_set
+            .add_shell(AngularMomentum::P, &[1.0], &[0.8])
+            .unwrap();
+        assert!(basis_set.memory_footprint() > footprint);
+
+        let mut library = BasisSetLibrary::new();
+        let empty_footprint = library.memory_footprint();
+        library.insert(BasisSetAssignmentType::Atom("H".to_string()), basis_set);
+        assert!(library.memory_footprint() > empty_footprint + footprint);
+    }
+
+    #[test]
+    fn test_from_iterator() {
+        let basis_set = sample_basis_set();
+
+        let collected: AtomicBasisSet = basis_set
+            .into_iter()
+            .map(|(am, contraction)| (am, contraction.clone()))
+            .collect();
+        assert_eq!(collected, basis_set);
+
+        let s_only: AtomicBasisSet = basis_set
+            .into_iter()
+            .filter(|(am, _)| *am == AngularMomentum::S)
+            .map(|(am, contraction)| (am, contraction.clone()))
+            .collect();
+        assert_eq!(s_only.get_num_contracted_functions(), 2);
+        assert_eq!(s_only.get_highest_angular_momentum(), AngularMomentum::S);
+
+        let with_sentinel: AtomicBasisSet = [('s', 0.5), ('T', 0.2)]
+            .into_iter()
+            .map(|(letter, exponental)| {
+                let mut contraction = SegmentedContraction::new();
+                contraction.add(1.0, exponental);
+                (
+                    AngularMomentum::try_from(letter)
+                        .unwrap_or(AngularMomentum::UnsupportedAngularMomentum),
+                    contraction,
+                )
+            })
+            .collect();
+        assert_eq!(with_sentinel.get_num_contracted_functions(), 1);
+        assert_eq!(
+            with_sentinel.get_highest_angular_momentum(),
+            AngularMomentum::S
+        );
+    }
+
+    #[test]
+    fn test_into_contractions() {
+        let basis_set = sample_basis_set();
+        let expected: Vec<(AngularMomentum, SegmentedContraction)> = basis_set
+            .into_iter()
+            .map(|(am, contraction)| (am, contraction.clone()))
+            .collect();
+        let num_contracted_functions = basis_set.get_num_contracted_functions();
+
+        let contractions: Vec<_> = basis_set.clone().into_contractions().collect();
+        assert_eq!(contractions.len(), num_contracted_functions);
+        assert_eq!(contractions, expected);
+        assert_eq!(contractions[0].0, AngularMomentum::S);
+
+        let rebuilt: AtomicBasisSet = basis_set.clone().into_contractions().collect();
+        assert_eq!(rebuilt, basis_set);
+    }
+
+    #[test]
+    fn test_iter_in_order() {
+        let basis_set = sample_basis_set();
+
+        let order = [AngularMomentum::D, AngularMomentum::P, AngularMomentum::S];
+        let shells: Vec<(AngularMomentum, usize)> = basis_set
+            .iter_in_order(&order)
+            .map(|(am, contraction)| (am, contraction.get_num_primitives()))
+            .collect();
+        assert_eq!(
+            shells,
+            vec![
+                (AngularMomentum::P, 1),
+                (AngularMomentum::S, 2),
+                (AngularMomentum::S, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spherical_or_cartesian() {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_shell(AngularMomentum::D, &[1.0], &[0.6])
+            .unwrap();
+        assert_eq!(basis_set.get_spherical(), SphericalOrCartesian::Spherical);
+        assert_eq!(basis_set.get_num_basis_functions(), 5);
+
+        basis_set.set_spherical(SphericalOrCartesian::Cartesian);
+        assert_eq!(basis_set.get_spherical(), SphericalOrCartesian::Cartesian);
+        assert_eq!(basis_set.get_num_basis_functions(), 6);
+        assert_eq!(
+            basis_set.decontract().get_spherical(),
+            SphericalOrCartesian::Cartesian
+        );
+    }
+
+    #[test]
+    fn test_sample_radial() {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_shell(AngularMomentum::S, &[0.5], &[2.0])
+            .unwrap()
+            .add_shell(AngularMomentum::P, &[1.0], &[0.8])
+            .unwrap();
+
+        let samples = basis_set.sample_radial(&[0.0, 1.0, 2.0]);
+        assert_eq!(samples.len(), 2);
+        let (s_am, s_index, s_values) = &samples[0];
+        assert_eq!((*s_am, *s_index), (AngularMomentum::S, 0));
+        assert_abs_diff_eq!(s_values[0], 0.5);
+        assert_abs_diff_eq!(s_values[1], 0.5 * (-2.0f64).exp());
+
+        let (p_am, p_index, p_values) = &samples[1];
+        assert_eq!((*p_am, *p_index), (AngularMomentum::P, 0));
+        assert_eq!(p_values.len(), 3);
+        assert_abs_diff_eq!(p_values[0], 0.0);
+        assert!(p_values[1] > 0.0);
     }
 }
@@ -0,0 +1,201 @@
+// Symbols of the elements up to Og, indexed by atomic number - 1
+const ELEMENT_SYMBOLS: [&str; 118] = [
+    "H", "He", "Li", "Be", "B", "C", "N", "O", "F", "Ne", "Na", "Mg", "Al", "Si", "P", "S", "Cl",
+    "Ar", "K", "Ca", "Sc", "Ti", "V", "Cr", "Mn", "Fe", "Co", "Ni", "Cu", "Zn", "Ga", "Ge", "As",
+    "Se", "Br", "Kr", "Rb", "Sr", "Y", "Zr", "Nb", "Mo", "Tc", "Ru", "Rh", "Pd", "Ag", "Cd", "In",
+    "Sn", "Sb", "Te", "I", "Xe", "Cs", "Ba", "La", "Ce", "Pr", "Nd", "Pm", "Sm", "Eu", "Gd", "Tb",
+    "Dy", "Ho", "Er", "Tm", "Yb", "Lu", "Hf", "Ta", "W", "Re", "Os", "Ir", "Pt", "Au", "Hg", "Tl",
+    "Pb", "Bi", "Po", "At", "Rn", "Fr", "Ra", "Ac", "Th", "Pa", "U", "Np", "Pu", "Am", "Cm", "Bk",
+    "Cf", "Es", "Fm", "Md", "No", "Lr", "Rf", "Db", "Sg", "Bh", "Hs", "Mt", "Ds", "Rg", "Cn", "Nh",
+    "Fl", "Mc", "Lv", "Ts", "Og",
+];
+
+fn normalize_element(atom: &str) -> Option<String> {
+    ELEMENT_SYMBOLS
+        .iter()
+        .find(|symbol| symbol.eq_ignore_ascii_case(atom))
+        .map(|symbol| symbol.to_string())
+}
+
+/// Centers compare by the bits of their coordinates, so that assignments can be used as keys
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BasisSetAssignmentType {
+    // Basis set for a type of atom
+    Atom(String),
+    // Basis set for a particle in the molecule, specified by the index starting with 0
+    ParticleIndex(i32),
+    // Basis set for whatever sits at the position
+    Center { x: f64, y: f64, z: f64 },
+    // Basis set of the element on a ghost atom without nuclear charge or electrons, as used in
+    // counterpoise corrections. The element is empty for a bare `Bq` center.
+    Ghost(String),
+}
+
+impl BasisSetAssignmentType {
+    fn center_bits(&self) -> Option<[u64; 3]> {
+        match self {
+            BasisSetAssignmentType::Center { x, y, z } => {
+                Some([x.to_bits(), y.to_bits(), z.to_bits()])
+            }
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for BasisSetAssignmentType {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BasisSetAssignmentType::Atom(a), BasisSetAssignmentType::Atom(b)) => a == b,
+            (
+                BasisSetAssignmentType::ParticleIndex(a),
+                BasisSetAssignmentType::ParticleIndex(b),
+            ) => a == b,
+            (BasisSetAssignmentType::Center { .. }, BasisSetAssignmentType::Center { .. }) => {
+                self.center_bits() == other.center_bits()
+            }
+            (BasisSetAssignmentType::Ghost(a), BasisSetAssignmentType::Ghost(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for BasisSetAssignmentType {}
+
+impl std::hash::Hash for BasisSetAssignmentType {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            BasisSetAssignmentType::Atom(atom) => atom.hash(state),
+            BasisSetAssignmentType::ParticleIndex(index) => index.hash(state),
+            BasisSetAssignmentType::Center { .. } => self.center_bits().hash(state),
+            BasisSetAssignmentType::Ghost(atom) => atom.hash(state),
+        }
+    }
+}
+
+/// Writes the assignment the way it appears on the first line of a basis set block
+impl std::fmt::Display for BasisSetAssignmentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BasisSetAssignmentType::Atom(atom) => write!(f, "{}", atom),
+            BasisSetAssignmentType::ParticleIndex(index) => write!(f, "{}", index),
+            // Debug keeps the decimal point of whole numbers, e.g. 0.0 rather than 0, so the
+            // coordinates are not read back as a particle index
+            BasisSetAssignmentType::Center { x, y, z } => write!(f, "{:?} {:?} {:?}", x, y, z),
+            BasisSetAssignmentType::Ghost(atom) if atom.is_empty() => write!(f, "Bq"),
+            BasisSetAssignmentType::Ghost(atom) => write!(f, "{}-Bq", atom),
+        }
+    }
+}
+
+impl BasisSetAssignmentType {
+    /// Atom of the element with the atomic number, e.g. `C` for 6
+    pub fn from_atomic_number(atomic_number: usize) -> Option<BasisSetAssignmentType> {
+        atomic_number
+            .checked_sub(1)
+            .and_then(|index| ELEMENT_SYMBOLS.get(index))
+            .map(|symbol| BasisSetAssignmentType::Atom(symbol.to_string()))
+    }
+
+    /// Canonical form of the assignment, with the element symbol capitalized as in the periodic
+    /// table, e.g. `c` -> `C` and `NA` -> `Na`. `None` if the atom is not a known element.
+    /// Ghosts are normalized by their element. Particle indices, centers and bare ghosts are
+    /// returned as they are.
+    pub fn normalize(&self) -> Option<BasisSetAssignmentType> {
+        match self {
+            BasisSetAssignmentType::Atom(atom) => {
+                normalize_element(atom).map(BasisSetAssignmentType::Atom)
+            }
+            BasisSetAssignmentType::Ghost(atom) if !atom.is_empty() => {
+                normalize_element(atom).map(BasisSetAssignmentType::Ghost)
+            }
+            BasisSetAssignmentType::ParticleIndex(_)
+            | BasisSetAssignmentType::Center { .. }
+            | BasisSetAssignmentType::Ghost(_) => Some(self.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::BasisSetAssignmentType;
+
+    #[test]
+    fn test_normalize() {
+        for (atom, expected) in [("he", "He"), ("C", "C"), ("NA", "Na"), ("og", "Og")] {
+            assert_eq!(
+                BasisSetAssignmentType::Atom(atom.to_string()).normalize(),
+                Some(BasisSetAssignmentType::Atom(expected.to_string()))
+            );
+        }
+        assert_eq!(
+            BasisSetAssignmentType::Atom("Qz".to_string()).normalize(),
+            None
+        );
+        assert_eq!(
+            BasisSetAssignmentType::Atom("Cx".to_string()).normalize(),
+            None
+        );
+        assert_eq!(
+            BasisSetAssignmentType::from_atomic_number(6),
+            Some(BasisSetAssignmentType::Atom("C".to_string()))
+        );
+        assert_eq!(BasisSetAssignmentType::from_atomic_number(0), None);
+        assert_eq!(BasisSetAssignmentType::from_atomic_number(119), None);
+        assert_eq!(
+            BasisSetAssignmentType::ParticleIndex(3).normalize(),
+            Some(BasisSetAssignmentType::ParticleIndex(3))
+        );
+    }
+
+    #[test]
+    fn test_center() {
+        let center = BasisSetAssignmentType::Center {
+            x: 0.0,
+            y: 0.0,
+            z: 1.5,
+        };
+        assert_eq!(center.to_string(), "0.0 0.0 1.5");
+        assert_eq!(center.normalize(), Some(center.clone()));
+        assert_ne!(
+            center,
+            BasisSetAssignmentType::Center {
+                x: 0.0,
+                y: 0.0,
+                z: -1.5
+            }
+        );
+
+        let keys: HashSet<BasisSetAssignmentType> = [
+            center.clone(),
+            center.clone(),
+            BasisSetAssignmentType::Atom("C".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(keys.len(), 2);
+        assert!(keys.contains(&center));
+    }
+
+    #[test]
+    fn test_ghost() {
+        let ghost = BasisSetAssignmentType::Ghost("c".to_string());
+        assert_eq!(ghost.to_string(), "c-Bq");
+        assert_eq!(
+            ghost.normalize(),
+            Some(BasisSetAssignmentType::Ghost("C".to_string()))
+        );
+        assert_ne!(ghost, BasisSetAssignmentType::Atom("c".to_string()));
+        assert_eq!(
+            BasisSetAssignmentType::Ghost("Qz".to_string()).normalize(),
+            None
+        );
+
+        let bare_ghost = BasisSetAssignmentType::Ghost(String::new());
+        assert_eq!(bare_ghost.to_string(), "Bq");
+        assert_eq!(bare_ghost.normalize(), Some(bare_ghost.clone()));
+    }
+}
@@ -0,0 +1,123 @@
+/// Effective core potential
+use std::vec::Vec;
+
+use super::angular_momentum::AngularMomentum;
+
+/// Radial term of an effective core potential, coefficient * r^(power - 2) * exp(-alpha r^2)
+#[derive(Clone, Copy)]
+pub struct EcpTerm {
+    power: i32,
+    exponental: f64,
+    coefficient: f64,
+}
+
+impl EcpTerm {
+    pub fn new(power: i32, exponental: f64, coefficient: f64) -> Self {
+        EcpTerm {
+            power,
+            exponental,
+            coefficient,
+        }
+    }
+
+    pub fn power(&self) -> i32 {
+        self.power
+    }
+
+    pub fn exponental(&self) -> f64 {
+        self.exponental
+    }
+
+    pub fn coefficient(&self) -> f64 {
+        self.coefficient
+    }
+}
+
+#[derive(Clone)]
+pub struct EffectiveCorePotential {
+    num_core_electrons: usize,
+    max_angular_momentum: AngularMomentum,
+    // Potential of the angular momentum above the projected ones, the "ul" part
+    local_potential: Vec<EcpTerm>,
+    // Projected potentials with the local part subtracted, indexed by angular momentum
+    projected_potentials: Vec<Vec<EcpTerm>>,
+}
+
+impl EffectiveCorePotential {
+    pub fn new(num_core_electrons: usize, max_angular_momentum: AngularMomentum) -> Self {
+        EffectiveCorePotential {
+            num_core_electrons,
+            max_angular_momentum,
+            local_potential: vec![],
+            projected_potentials: vec![],
+        }
+    }
+
+    pub fn get_num_core_electrons(&self) -> usize {
+        self.num_core_electrons
+    }
+
+    pub fn get_max_angular_momentum(&self) -> AngularMomentum {
+        self.max_angular_momentum
+    }
+
+    pub fn add_local_term(&mut self, term: EcpTerm) -> &mut Self {
+        self.local_potential.push(term);
+        self
+    }
+
+    /// Adds a term to the projected potential of the angular momentum. The call is ignored for
+    /// `UnsupportedAngularMomentum`, which has no slot.
+    pub fn add_projected_term(
+        &mut self,
+        angular_momentum: AngularMomentum,
+        term: EcpTerm,
+    ) -> &mut Self {
+        if angular_momentum == AngularMomentum::UnsupportedAngularMomentum {
+            return self;
+        }
+        let angular_momentum_num = angular_momentum as usize;
+        while self.projected_potentials.len() <= angular_momentum_num {
+            self.projected_potentials.push(vec![]);
+        }
+        self.projected_potentials[angular_momentum_num].push(term);
+        self
+    }
+
+    pub fn local_potential(&self) -> &[EcpTerm] {
+        &self.local_potential
+    }
+
+    pub fn projected_potential(&self, angular_momentum: AngularMomentum) -> Option<&[EcpTerm]> {
+        self.projected_potentials
+            .get(angular_momentum as usize)
+            .map(|terms| terms.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EcpTerm, EffectiveCorePotential};
+    use crate::details::angular_momentum::AngularMomentum;
+
+    #[test]
+    fn test_add_projected_term() {
+        let mut ecp = EffectiveCorePotential::new(10, AngularMomentum::D);
+        ecp.add_projected_term(AngularMomentum::P, EcpTerm::new(2, 1.5, -0.5))
+            .add_projected_term(
+                AngularMomentum::UnsupportedAngularMomentum,
+                EcpTerm::new(2, 1.0, 1.0),
+            );
+        assert!(ecp
+            .projected_potential(AngularMomentum::S)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            ecp.projected_potential(AngularMomentum::P).unwrap().len(),
+            1
+        );
+        assert!(ecp
+            .projected_potential(AngularMomentum::UnsupportedAngularMomentum)
+            .is_none());
+    }
+}
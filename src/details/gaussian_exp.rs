@@ -1,6 +1,99 @@
-use std::vec::Vec;
+use std::{f64::consts::PI, vec::Vec};
 
-#[derive(Clone, Copy)]
+use super::{angular_momentum::AngularMomentum, atomic_basis_set::BasisSetValidationError};
+
+/// n!!, with 0!! = (-1)!! = 1
+fn double_factorial(n: i32) -> f64 {
+    (1..=n).rev().step_by(2).map(|i| i as f64).product()
+}
+
+/// Overlap of two primitive Cartesian Gaussians x^l exp(-alpha r^2) on the same center
+fn primitive_overlap(l: usize, exponental_a: f64, exponental_b: f64) -> f64 {
+    let p = exponental_a + exponental_b;
+    (PI / p).powf(1.5) * double_factorial(2 * l as i32 - 1) / (2.0 * p).powi(l as i32)
+}
+
+/// Overlap of two contracted functions on the same center, sum_ij a_i b_j <g_i|g_j> with signed
+/// coefficients, a positive definite quadratic form for a = b
+fn contraction_overlap(a: &SegmentedContraction, b: &SegmentedContraction, l: usize) -> f64 {
+    a.iter()
+        .map(|primitive_a| {
+            b.iter()
+                .map(|primitive_b| {
+                    primitive_a.coefficient
+                        * primitive_b.coefficient
+                        * primitive_overlap(l, primitive_a.exponental, primitive_b.exponental)
+                })
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+/// Overlap <a|b> of two contracted functions of the same angular momentum sharing a center.
+/// The coefficients are taken as they are stored, so the result is 1 for a contraction with
+/// itself once it went through `normalize_primitives` and `normalize_contraction`.
+/// `UnsupportedAngularMomentum` gives 0.
+pub fn overlap_same_center(
+    a: &SegmentedContraction,
+    b: &SegmentedContraction,
+    angular_momentum: AngularMomentum,
+) -> f64 {
+    if angular_momentum == AngularMomentum::UnsupportedAngularMomentum {
+        return 0.0;
+    }
+    contraction_overlap(a, b, angular_momentum as usize)
+}
+
+/// Product of two s-type Gaussians exp(-a |r-A|^2) exp(-b |r-B|^2), which by the Gaussian
+/// product theorem is the Gaussian prefactor * exp(-p |r-P|^2)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GaussianProduct {
+    /// Combined exponental p = a + b
+    pub exponent: f64,
+    /// Reduced exponental mu = ab / p
+    pub reduced_exponent: f64,
+    /// Center P = (aA + bB) / p
+    pub center: [f64; 3],
+    /// exp(-mu |A-B|^2)
+    pub prefactor: f64,
+}
+
+/// Applies the Gaussian product theorem to the exponentals a and b centered on ra and rb
+pub fn gaussian_product(a: f64, b: f64, ra: [f64; 3], rb: [f64; 3]) -> GaussianProduct {
+    let exponent = a + b;
+    let reduced_exponent = a * b / exponent;
+    let center = [0, 1, 2].map(|i| (a * ra[i] + b * rb[i]) / exponent);
+    let distance_squared: f64 = (0..3).map(|i| (ra[i] - rb[i]).powi(2)).sum();
+    GaussianProduct {
+        exponent,
+        reduced_exponent,
+        center,
+        prefactor: (-reduced_exponent * distance_squared).exp(),
+    }
+}
+
+/// Whether the values are equal within a relative tolerance
+pub(crate) fn relative_eq(a: f64, b: f64, relative_tolerance: f64) -> bool {
+    (a - b).abs() <= relative_tolerance * a.abs().max(b.abs())
+}
+
+/// Whether any two of the values are equal within a relative tolerance
+pub(crate) fn has_duplicate_values(values: &[f64], relative_tolerance: f64) -> bool {
+    values.iter().enumerate().any(|(index, a)| {
+        values[index + 1..]
+            .iter()
+            .any(|b| relative_eq(*a, *b, relative_tolerance))
+    })
+}
+
+/// Normalization constant of a primitive Cartesian Gaussian x^l exp(-alpha r^2)
+fn primitive_normalization(l: usize, exponental: f64) -> f64 {
+    (2.0 * exponental / PI).powf(0.75) * (4.0 * exponental).powf(l as f64 / 2.0)
+        / double_factorial(2 * l as i32 - 1).sqrt()
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GaussianPrimitive {
     coefficient: f64,
     exponental: f64,
@@ -21,13 +114,70 @@ impl GaussianPrimitive {
     pub fn exponental(&self) -> f64 {
         self.exponental
     }
+
+    /// Compares both the coefficient and the exponental within a relative tolerance
+    pub fn approx_eq(&self, other: &GaussianPrimitive, relative_tolerance: f64) -> bool {
+        relative_eq(self.coefficient, other.coefficient, relative_tolerance)
+            && relative_eq(self.exponental, other.exponental, relative_tolerance)
+    }
+}
+
+/// Exact comparison of the stored bits, so that e.g. a round trip through text can be checked
+/// to be lossless. Use `approx_eq` to compare values within a tolerance.
+impl PartialEq for GaussianPrimitive {
+    fn eq(&self, other: &Self) -> bool {
+        self.coefficient.to_bits() == other.coefficient.to_bits()
+            && self.exponental.to_bits() == other.exponental.to_bits()
+    }
+}
+
+/// Compares exactly, including the shell group, see `approx_eq` for a comparison within a
+/// tolerance
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SegmentedContraction {
+    primitives: Vec<GaussianPrimitive>,
+    // Shared by the contractions read from one combined shell, e.g. the S and P parts of an
+    // SP shell, so writers can emit them together again
+    shell_group: Option<usize>,
 }
 
-pub struct SegmentedContraction(Vec<GaussianPrimitive>);
+impl Default for SegmentedContraction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl SegmentedContraction {
     pub fn new() -> Self {
-        SegmentedContraction(vec![])
+        SegmentedContraction {
+            primitives: vec![],
+            shell_group: None,
+        }
+    }
+
+    /// Empty contraction with room for n primitives
+    pub fn with_capacity(n: usize) -> Self {
+        SegmentedContraction {
+            primitives: Vec::with_capacity(n),
+            shell_group: None,
+        }
+    }
+
+    /// Even-tempered contraction of n primitives with coefficient 1 and exponentals
+    /// alpha * beta^i, i = 0..n. The coefficients are not normalized, so the angular momentum
+    /// does not change the primitives.
+    pub fn even_tempered(
+        _angular_momentum: AngularMomentum,
+        alpha: f64,
+        beta: f64,
+        n: usize,
+    ) -> Self {
+        let mut segmented_contraction = SegmentedContraction::with_capacity(n);
+        for i in 0..n {
+            segmented_contraction.add(1.0, alpha * beta.powi(i as i32));
+        }
+        segmented_contraction
     }
 
     pub fn add(&mut self, coefficient: f64, exponental: f64) -> &mut Self {
@@ -35,15 +185,748 @@ impl SegmentedContraction {
     }
 
     pub fn add_primitive(&mut self, primitive: GaussianPrimitive) -> &mut Self {
-        self.0.push(primitive);
+        self.primitives.push(primitive);
         self
     }
 
     pub fn get_num_primitives(&self) -> usize {
-        self.0.len()
+        self.primitives.len()
     }
 
     pub fn get(&self, index: usize) -> Option<&GaussianPrimitive> {
-        self.0.get(index)
+        self.primitives.get(index)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, GaussianPrimitive> {
+        self.primitives.iter()
+    }
+
+    /// Exponentals of the primitives in order, without collecting them
+    pub fn exponents(&self) -> impl Iterator<Item = f64> + '_ {
+        self.primitives.iter().map(|primitive| primitive.exponental)
+    }
+
+    /// Coefficients of the primitives in order, without collecting them
+    pub fn coefficients(&self) -> impl Iterator<Item = f64> + '_ {
+        self.primitives
+            .iter()
+            .map(|primitive| primitive.coefficient)
+    }
+
+    /// Bytes allocated on the heap for the primitives
+    pub(crate) fn heap_bytes(&self) -> usize {
+        self.primitives.capacity() * std::mem::size_of::<GaussianPrimitive>()
+    }
+
+    pub fn coefficient_sum(&self) -> f64 {
+        self.iter().map(|primitive| primitive.coefficient).sum()
+    }
+
+    /// Primitive with the largest absolute coefficient, `None` for an empty contraction
+    pub fn dominant_primitive(&self) -> Option<&GaussianPrimitive> {
+        self.iter()
+            .max_by(|a, b| a.coefficient.abs().total_cmp(&b.coefficient.abs()))
+    }
+
+    /// Identifier of the combined shell the contraction was read from, `None` if it stands on
+    /// its own
+    pub fn shell_group(&self) -> Option<usize> {
+        self.shell_group
+    }
+
+    pub fn set_shell_group(&mut self, shell_group: Option<usize>) -> &mut Self {
+        self.shell_group = shell_group;
+        self
+    }
+
+    /// Whether both contractions hold exactly the same exponentals in the same order
+    pub fn shares_exponents(&self, other: &SegmentedContraction) -> bool {
+        self.primitives.len() == other.primitives.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(a, b)| a.exponental == b.exponental)
+    }
+
+    /// Compares the primitives pairwise within a relative tolerance
+    pub fn approx_eq(&self, other: &SegmentedContraction, relative_tolerance: f64) -> bool {
+        self.primitives.len() == other.primitives.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(a, b)| a.approx_eq(b, relative_tolerance))
+    }
+
+    /// Whether two primitives share the same exponental within a relative tolerance, which
+    /// usually means a primitive was repeated by accident
+    pub fn has_duplicate_exponents(&self, relative_tolerance: f64) -> bool {
+        let exponentals: Vec<f64> = self.iter().map(|primitive| primitive.exponental).collect();
+        has_duplicate_values(&exponentals, relative_tolerance)
+    }
+
+    /// Multiplies every exponental by the factor, e.g. zeta^2 when adapting to another element
+    pub fn scale_exponents(&mut self, factor: f64) -> &mut Self {
+        for primitive in self.primitives.iter_mut() {
+            primitive.exponental *= factor;
+        }
+        self
+    }
+
+    /// Orders the primitives from the tightest to the most diffuse, each coefficient staying
+    /// with its exponental. Primitives with equal exponentals keep their order.
+    pub fn sort_by_exponent_descending(&mut self) -> &mut Self {
+        self.primitives
+            .sort_by(|a, b| b.exponental.total_cmp(&a.exponental));
+        self
+    }
+
+    /// Drops the primitives with an exponental below `min_exponent`
+    pub fn remove_diffuse(&mut self, min_exponent: f64) -> &mut Self {
+        self.primitives
+            .retain(|primitive| primitive.exponental >= min_exponent);
+        self
+    }
+
+    /// Drops the primitives whose absolute coefficient is below `rel_threshold` times the largest
+    /// one. The dominant primitive is always kept, so the contraction is never left empty. The
+    /// coefficients are not touched, call `normalize_contraction` afterwards to renormalize.
+    pub fn prune_small_coefficients(&mut self, rel_threshold: f64) -> &mut Self {
+        if let Some(max_abs_coefficient) = self
+            .dominant_primitive()
+            .map(|primitive| primitive.coefficient.abs())
+        {
+            let threshold = (rel_threshold * max_abs_coefficient).min(max_abs_coefficient);
+            self.primitives
+                .retain(|primitive| primitive.coefficient.abs() >= threshold);
+        }
+        self
+    }
+
+    /// Multiplies the coefficient of each primitive by its normalization constant
+    /// N(l, alpha) = (2 alpha / pi)^(3/4) * (4 alpha)^(l/2) / sqrt((2l-1)!!).
+    /// Contractions under `UnsupportedAngularMomentum` are left untouched.
+    pub fn normalize_primitives(&mut self, angular_momentum: AngularMomentum) -> &mut Self {
+        if angular_momentum == AngularMomentum::UnsupportedAngularMomentum {
+            return self;
+        }
+        let l = angular_momentum as usize;
+        for primitive in self.primitives.iter_mut() {
+            primitive.coefficient *= primitive_normalization(l, primitive.exponental);
+        }
+        self
+    }
+
+    /// Self-overlap of the contracted function, sum_ij c_i c_j <g_i|g_j>
+    fn self_overlap(&self, l: usize) -> f64 {
+        contraction_overlap(self, self, l)
+    }
+
+    /// Rescales the coefficients so the contracted function has a self-overlap of 1.
+    /// The coefficients are taken as they are stored, so the primitive normalization
+    /// should be applied first if the file stores coefficients of unnormalized primitives.
+    /// Signs are kept. Contractions under `UnsupportedAngularMomentum` are left untouched.
+    pub fn normalize_contraction(&mut self, angular_momentum: AngularMomentum) -> &mut Self {
+        if angular_momentum == AngularMomentum::UnsupportedAngularMomentum {
+            return self;
+        }
+        let overlap = self.self_overlap(angular_momentum as usize);
+        if overlap > 0.0 {
+            let scale = 1.0 / overlap.sqrt();
+            for primitive in self.primitives.iter_mut() {
+                primitive.coefficient *= scale;
+            }
+        }
+        self
+    }
+
+    /// Radial part of the contracted function at distance r, sum_i c_i r^l exp(-alpha_i r^2).
+    /// Returns 0 for `UnsupportedAngularMomentum`.
+    pub fn evaluate_radial(&self, angular_momentum: AngularMomentum, r: f64) -> f64 {
+        if angular_momentum == AngularMomentum::UnsupportedAngularMomentum {
+            return 0.0;
+        }
+        let r_l = r.powi(angular_momentum as i32);
+        self.iter()
+            .map(|primitive| primitive.coefficient * r_l * (-primitive.exponental * r * r).exp())
+            .sum()
+    }
+
+    /// Mean radius <r> of the contracted function, from the radial integrals
+    /// int r^n exp(-p r^2) dr = Gamma((n+1)/2) / (2 p^((n+1)/2)). The coefficients are taken as
+    /// they are stored, the overall normalization cancels out. Returns 0 for a contraction
+    /// without primitives or under `UnsupportedAngularMomentum`.
+    pub fn radial_expectation(&self, angular_momentum: AngularMomentum) -> f64 {
+        if angular_momentum == AngularMomentum::UnsupportedAngularMomentum
+            || self.primitives.is_empty()
+        {
+            return 0.0;
+        }
+        let l = angular_momentum as i32;
+        let pair_sum = |power: f64| -> f64 {
+            self.iter()
+                .flat_map(|a| {
+                    self.iter().map(move |b| {
+                        a.coefficient * b.coefficient * (a.exponental + b.exponental).powf(-power)
+                    })
+                })
+                .sum()
+        };
+        // Gamma(l + 2) = (l + 1)! and Gamma(l + 3/2) = (2l + 1)!! sqrt(pi) / 2^(l + 1)
+        let gamma_ratio = (1..=l + 1).map(|i| i as f64).product::<f64>()
+            / (double_factorial(2 * l + 1) * PI.sqrt() / 2f64.powi(l + 1));
+        gamma_ratio * pair_sum(l as f64 + 2.0) / pair_sum(l as f64 + 1.5)
+    }
+}
+
+impl<'a> IntoIterator for &'a SegmentedContraction {
+    type Item = &'a GaussianPrimitive;
+
+    type IntoIter = std::slice::Iter<'a, GaussianPrimitive>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// General contraction, several contracted functions of the same angular momentum sharing
+/// one set of exponentals
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneralContraction {
+    exponentals: Vec<f64>,
+    // One coefficient vector per contracted function, aligned with the exponentals
+    coefficients: Vec<Vec<f64>>,
+}
+
+impl GeneralContraction {
+    pub fn new(exponentals: Vec<f64>) -> Self {
+        GeneralContraction {
+            exponentals,
+            coefficients: vec![],
+        }
+    }
+
+    /// Adds a contracted function, which needs one coefficient per exponental
+    pub fn add_contracted_function(
+        &mut self,
+        coefficients: Vec<f64>,
+    ) -> Result<&mut Self, BasisSetValidationError> {
+        if coefficients.len() != self.exponentals.len() {
+            return Err(BasisSetValidationError::MismatchedLengths {
+                num_coefficients: coefficients.len(),
+                num_exponentals: self.exponentals.len(),
+            });
+        }
+        self.coefficients.push(coefficients);
+        Ok(self)
+    }
+
+    pub fn get_num_primitives(&self) -> usize {
+        self.exponentals.len()
+    }
+
+    pub fn get_num_contracted_functions(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    pub fn exponentals(&self) -> &[f64] {
+        &self.exponentals
+    }
+
+    pub fn coefficients(&self, index: usize) -> Option<&[f64]> {
+        self.coefficients.get(index).map(|c| c.as_slice())
+    }
+
+    /// Splits the general contraction into one segmented contraction per contracted function
+    pub fn to_segmented_contractions(&self) -> Vec<SegmentedContraction> {
+        self.coefficients
+            .iter()
+            .map(|coefficients| {
+                let mut segmented_contraction = SegmentedContraction::new();
+                for (coefficient, exponental) in coefficients.iter().zip(self.exponentals.iter()) {
+                    segmented_contraction.add(*coefficient, *exponental);
+                }
+                segmented_contraction
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::details::{
+        angular_momentum::AngularMomentum, atomic_basis_set::BasisSetValidationError,
+    };
+
+    use super::{
+        double_factorial, gaussian_product, overlap_same_center, primitive_overlap,
+        GaussianPrimitive, GeneralContraction, SegmentedContraction,
+    };
+
+    #[test]
+    fn test_double_factorial() {
+        assert_abs_diff_eq!(double_factorial(-1), 1.0);
+        assert_abs_diff_eq!(double_factorial(0), 1.0);
+        assert_abs_diff_eq!(double_factorial(5), 15.0);
+        assert_abs_diff_eq!(double_factorial(6), 48.0);
+    }
+
+    #[test]
+    fn test_normalize_primitives() {
+        let mut s_contraction = SegmentedContraction::new();
+        s_contraction.add(1.0, 0.5);
+        s_contraction.normalize_primitives(AngularMomentum::S);
+        // (2 * 0.5 / pi)^(3/4)
+        assert_abs_diff_eq!(
+            s_contraction.get(0).unwrap().coefficient(),
+            PI.powf(-0.75),
+            epsilon = 1e-12
+        );
+
+        let mut d_contraction = SegmentedContraction::new();
+        d_contraction.add(2.0, 1.0);
+        d_contraction.normalize_primitives(AngularMomentum::D);
+        // 2 * (2 / pi)^(3/4) * 4 / sqrt(3)
+        assert_abs_diff_eq!(
+            d_contraction.get(0).unwrap().coefficient(),
+            2.0 * (2.0 / PI).powf(0.75) * 4.0 / 3.0_f64.sqrt(),
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(d_contraction.get(0).unwrap().exponental(), 1.0);
+    }
+
+    #[test]
+    fn test_primitive_overlap() {
+        // Normalized primitives have unit self-overlap
+        for l in 0..4 {
+            let mut contraction = SegmentedContraction::new();
+            contraction.add(1.0, 1.7);
+            contraction.normalize_primitives(AngularMomentum::try_from(l).unwrap());
+            assert_abs_diff_eq!(contraction.self_overlap(l), 1.0, epsilon = 1e-12);
+        }
+
+        assert_abs_diff_eq!(
+            primitive_overlap(0, 0.5, 0.5),
+            PI.powf(1.5),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_normalize_contraction() {
+        // S shell of 6-311G for C
+        let mut contraction = SegmentedContraction::new();
+        contraction
+            .add(0.00196665, 4563.240)
+            .add(0.0152306, 682.0240)
+            .add(0.0761269, 154.9730)
+            .add(0.2608010, 44.45530)
+            .add(0.6164620, 13.02900)
+            .add(0.2210060, 1.827730);
+
+        contraction
+            .normalize_primitives(AngularMomentum::S)
+            .normalize_contraction(AngularMomentum::S);
+        assert_abs_diff_eq!(contraction.self_overlap(0), 1.0, epsilon = 1e-12);
+        // Exponents are not affected
+        assert_abs_diff_eq!(
+            contraction.get(5).unwrap().exponental(),
+            1.827730,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_normalize_contraction_negative_coefficient() {
+        let mut contraction = SegmentedContraction::new();
+        contraction.add(-0.5, 4.0).add(1.0, 0.8);
+        contraction
+            .normalize_primitives(AngularMomentum::S)
+            .normalize_contraction(AngularMomentum::S);
+        assert_abs_diff_eq!(contraction.self_overlap(0), 1.0, epsilon = 1e-12);
+        assert!(contraction.get(0).unwrap().coefficient() < 0.0);
+        assert!(contraction.get(1).unwrap().coefficient() > 0.0);
+
+        // SP shell of 6-311G for C, the S part holds -0.00303068
+        let mut s_contraction = SegmentedContraction::new();
+        s_contraction
+            .add(0.114660, 20.96420)
+            .add(0.919999, 4.803310)
+            .add(-0.00303068, 1.459330);
+        s_contraction
+            .normalize_primitives(AngularMomentum::S)
+            .normalize_contraction(AngularMomentum::S);
+        assert_abs_diff_eq!(
+            overlap_same_center(&s_contraction, &s_contraction, AngularMomentum::S),
+            1.0,
+            epsilon = 1e-12
+        );
+        assert!(s_contraction.get(2).unwrap().coefficient() < 0.0);
+    }
+
+    #[test]
+    fn test_gaussian_product() {
+        let product = gaussian_product(1.0, 3.0, [0.0, 0.0, 0.0], [1.0, 2.0, 0.0]);
+        assert_eq!(product.exponent, 4.0);
+        assert_eq!(product.reduced_exponent, 0.75);
+        assert_eq!(product.center, [0.75, 1.5, 0.0]);
+        // |A-B|^2 = 5
+        assert_abs_diff_eq!(product.prefactor, (-3.75f64).exp(), epsilon = 1e-15);
+
+        // The product is the Gaussian centered on P at every point
+        let r = [0.3, -0.2, 0.5];
+        let distance_squared =
+            |center: [f64; 3]| -> f64 { (0..3).map(|i| (r[i] - center[i]).powi(2)).sum() };
+        assert_abs_diff_eq!(
+            (-distance_squared([0.0, 0.0, 0.0])).exp()
+                * (-3.0 * distance_squared([1.0, 2.0, 0.0])).exp(),
+            product.prefactor * (-product.exponent * distance_squared(product.center)).exp(),
+            epsilon = 1e-15
+        );
+
+        // Same center, no decay
+        let product = gaussian_product(0.5, 0.5, [1.0, 1.0, 1.0], [1.0, 1.0, 1.0]);
+        assert_eq!(product.center, [1.0, 1.0, 1.0]);
+        assert_eq!(product.prefactor, 1.0);
+    }
+
+    #[test]
+    fn test_overlap_same_center() {
+        let mut tight = SegmentedContraction::new();
+        tight.add(0.3, 4.0).add(0.7, 1.2);
+        tight
+            .normalize_primitives(AngularMomentum::P)
+            .normalize_contraction(AngularMomentum::P);
+        let mut diffuse = SegmentedContraction::new();
+        diffuse.add(1.0, 0.3);
+        diffuse
+            .normalize_primitives(AngularMomentum::P)
+            .normalize_contraction(AngularMomentum::P);
+
+        assert_abs_diff_eq!(
+            overlap_same_center(&tight, &tight, AngularMomentum::P),
+            1.0,
+            epsilon = 1e-12
+        );
+        let overlap = overlap_same_center(&tight, &diffuse, AngularMomentum::P);
+        assert!(overlap > 0.0 && overlap < 1.0);
+        assert_abs_diff_eq!(
+            overlap,
+            overlap_same_center(&diffuse, &tight, AngularMomentum::P),
+            epsilon = 1e-12
+        );
+        assert_eq!(
+            overlap_same_center(
+                &tight,
+                &diffuse,
+                AngularMomentum::UnsupportedAngularMomentum
+            ),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_coefficient_sum_and_dominant_primitive() {
+        // S shell of 6-311G for C
+        let mut contraction = SegmentedContraction::new();
+        contraction
+            .add(0.00196665, 4563.240)
+            .add(0.0152306, 682.0240)
+            .add(0.0761269, 154.9730)
+            .add(0.2608010, 44.45530)
+            .add(0.6164620, 13.02900)
+            .add(0.2210060, 1.827730);
+
+        assert_abs_diff_eq!(contraction.coefficient_sum(), 1.19159315, epsilon = 1e-12);
+        let dominant = contraction.dominant_primitive().unwrap();
+        assert_eq!(dominant.coefficient(), 0.6164620);
+        assert_eq!(dominant.exponental(), 13.02900);
+
+        let mut negative_contraction = SegmentedContraction::new();
+        negative_contraction.add(0.5, 2.0).add(-0.9, 0.4);
+        assert_eq!(
+            negative_contraction
+                .dominant_primitive()
+                .unwrap()
+                .exponental(),
+            0.4
+        );
+        assert!(SegmentedContraction::new().dominant_primitive().is_none());
+    }
+
+    #[test]
+    fn test_prune_small_coefficients() {
+        // S shell of 6-311G for C
+        let mut contraction = SegmentedContraction::new();
+        contraction
+            .add(0.00196665, 4563.240)
+            .add(0.0152306, 682.0240)
+            .add(0.0761269, 154.9730)
+            .add(0.2608010, 44.45530)
+            .add(0.6164620, 13.02900)
+            .add(0.2210060, 1.827730);
+
+        contraction.prune_small_coefficients(0.01);
+        assert_eq!(contraction.get_num_primitives(), 5);
+        assert_eq!(contraction.get(0).unwrap().exponental(), 682.0240);
+        assert_abs_diff_eq!(
+            contraction.coefficient_sum(),
+            1.19159315 - 0.00196665,
+            epsilon = 1e-12
+        );
+
+        // The sign does not matter, and the dominant primitive survives any threshold
+        let mut negative_contraction = SegmentedContraction::new();
+        negative_contraction
+            .add(0.5, 2.0)
+            .add(-0.9, 0.4)
+            .add(0.001, 0.1);
+        negative_contraction.prune_small_coefficients(0.5);
+        assert_eq!(negative_contraction.get_num_primitives(), 2);
+        negative_contraction.prune_small_coefficients(10.0);
+        assert_eq!(negative_contraction.get_num_primitives(), 1);
+        assert_eq!(negative_contraction.get(0).unwrap().coefficient(), -0.9);
+
+        let mut empty_contraction = SegmentedContraction::new();
+        empty_contraction.prune_small_coefficients(0.1);
+        assert_eq!(empty_contraction.get_num_primitives(), 0);
+    }
+
+    #[test]
+    fn test_evaluate_radial() {
+        let mut contraction = SegmentedContraction::new();
+        contraction.add(0.5, 2.0);
+
+        // 0.5 * exp(-2)
+        assert_abs_diff_eq!(
+            contraction.evaluate_radial(AngularMomentum::S, 1.0),
+            0.067667641618306,
+            epsilon = 1e-12
+        );
+        // 0.5 * 1.5^2 * exp(-2 * 1.5^2)
+        assert_abs_diff_eq!(
+            contraction.evaluate_radial(AngularMomentum::D, 1.5),
+            0.5 * 2.25 * (-4.5_f64).exp(),
+            epsilon = 1e-12
+        );
+
+        // Only S functions are nonzero at the origin
+        assert_abs_diff_eq!(contraction.evaluate_radial(AngularMomentum::S, 0.0), 0.5);
+        assert_abs_diff_eq!(contraction.evaluate_radial(AngularMomentum::P, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_radial_expectation() {
+        // <r> = 2 / sqrt(2 pi alpha) for a single S primitive
+        let mut s_contraction = SegmentedContraction::new();
+        s_contraction.add(0.8, 0.5);
+        assert_abs_diff_eq!(
+            s_contraction.radial_expectation(AngularMomentum::S),
+            2.0 / (2.0 * PI * 0.5).sqrt(),
+            epsilon = 1e-12
+        );
+
+        // <r> = 1 / sqrt(2 alpha) * 2! / Gamma(5/2) for a single P primitive
+        let mut p_contraction = SegmentedContraction::new();
+        p_contraction.add(1.0, 2.0);
+        assert_abs_diff_eq!(
+            p_contraction.radial_expectation(AngularMomentum::P),
+            2.0 / (0.75 * PI.sqrt()) / 2.0,
+            epsilon = 1e-12
+        );
+
+        // Adding a diffuse primitive moves the function outwards
+        let mut diffuse_contraction = s_contraction.clone();
+        diffuse_contraction.add(0.3, 0.05);
+        assert!(
+            diffuse_contraction.radial_expectation(AngularMomentum::S)
+                > s_contraction.radial_expectation(AngularMomentum::S)
+        );
+        assert_eq!(
+            SegmentedContraction::new().radial_expectation(AngularMomentum::S),
+            0.0
+        );
+        assert_eq!(
+            s_contraction.radial_expectation(AngularMomentum::UnsupportedAngularMomentum),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut contraction = SegmentedContraction::new();
+        contraction.add(0.3, 3.0).add(0.7, 0.5);
+
+        let primitives: Vec<_> = contraction.iter().collect();
+        assert_eq!(primitives.len(), contraction.get_num_primitives());
+        assert_abs_diff_eq!(primitives[1].coefficient(), 0.7);
+
+        let exponentals: Vec<f64> = (&contraction)
+            .into_iter()
+            .map(|primitive| primitive.exponental())
+            .collect();
+        assert_eq!(exponentals, vec![3.0, 0.5]);
+
+        for (index, (exponental, coefficient)) in contraction
+            .exponents()
+            .zip(contraction.coefficients())
+            .enumerate()
+        {
+            let primitive = contraction.get(index).unwrap();
+            assert_eq!(exponental, primitive.exponental());
+            assert_eq!(coefficient, primitive.coefficient());
+        }
+        assert_eq!(contraction.exponents().count(), 2);
+        assert_eq!(
+            contraction.coefficients().collect::<Vec<_>>(),
+            vec![0.3, 0.7]
+        );
+    }
+
+    #[test]
+    fn test_general_contraction() {
+        let mut general_contraction = GeneralContraction::new(vec![10.0, 1.0, 0.1]);
+        general_contraction
+            .add_contracted_function(vec![0.2, 0.5, 0.3])
+            .unwrap()
+            .add_contracted_function(vec![-0.1, 0.0, 1.1])
+            .unwrap();
+        assert_eq!(
+            general_contraction.add_contracted_function(vec![1.0, 0.0]),
+            Err(BasisSetValidationError::MismatchedLengths {
+                num_coefficients: 2,
+                num_exponentals: 3
+            })
+        );
+        assert_eq!(general_contraction.get_num_primitives(), 3);
+        assert_eq!(general_contraction.get_num_contracted_functions(), 2);
+        assert_eq!(general_contraction.coefficients(1).unwrap()[2], 1.1);
+        assert!(general_contraction.coefficients(2).is_none());
+
+        let segmented_contractions = general_contraction.to_segmented_contractions();
+        assert_eq!(segmented_contractions.len(), 2);
+        assert_abs_diff_eq!(
+            segmented_contractions[1].get(0).unwrap().coefficient(),
+            -0.1
+        );
+        assert_abs_diff_eq!(segmented_contractions[1].get(2).unwrap().exponental(), 0.1);
+    }
+
+    #[test]
+    fn test_sort_by_exponent_descending() {
+        let mut contraction = SegmentedContraction::new();
+        contraction
+            .add(0.6, 0.5)
+            .add(0.1, 30.0)
+            .add(0.3, 3.0)
+            .add(0.05, 0.1);
+        contraction.sort_by_exponent_descending();
+
+        let primitives: Vec<(f64, f64)> = contraction
+            .iter()
+            .map(|primitive| (primitive.coefficient(), primitive.exponental()))
+            .collect();
+        assert_eq!(
+            primitives,
+            vec![(0.1, 30.0), (0.3, 3.0), (0.6, 0.5), (0.05, 0.1)]
+        );
+    }
+
+    #[test]
+    fn test_scale_exponents() {
+        let mut contraction = SegmentedContraction::new();
+        contraction.add(0.3, 3.0).add(0.7, 0.5);
+        contraction.scale_exponents(1.44);
+
+        assert_abs_diff_eq!(
+            contraction.get(0).unwrap().exponental(),
+            4.32,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            contraction.get(1).unwrap().exponental(),
+            0.72,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(contraction.get(0).unwrap().coefficient(), 0.3);
+        assert_abs_diff_eq!(contraction.get(1).unwrap().coefficient(), 0.7);
+    }
+
+    #[test]
+    fn test_has_duplicate_exponents() {
+        let mut duplicated = SegmentedContraction::new();
+        duplicated
+            .add(0.3, 3.0)
+            .add(0.5, 1.2)
+            .add(0.2, 3.0 * (1.0 + 1e-12));
+        assert!(duplicated.has_duplicate_exponents(1e-9));
+
+        let mut clean = SegmentedContraction::new();
+        clean.add(0.3, 3.0).add(0.5, 1.2).add(0.2, 3.01);
+        assert!(!clean.has_duplicate_exponents(1e-9));
+        assert!(clean.has_duplicate_exponents(1e-2));
+    }
+
+    #[test]
+    fn test_exact_eq() {
+        assert_eq!(
+            GaussianPrimitive::new(0.5, 2.0),
+            GaussianPrimitive::new(0.5, 2.0)
+        );
+        assert_ne!(
+            GaussianPrimitive::new(0.5, 2.0),
+            GaussianPrimitive::new(0.5 + 1e-15, 2.0)
+        );
+        assert_ne!(
+            GaussianPrimitive::new(0.0, 2.0),
+            GaussianPrimitive::new(-0.0, 2.0)
+        );
+
+        let mut contraction = SegmentedContraction::new();
+        contraction.add(0.4, 3.0).add(0.6, 0.5);
+        let mut grouped_contraction = contraction.clone();
+        assert_eq!(contraction, grouped_contraction);
+        grouped_contraction.set_shell_group(Some(0));
+        assert_ne!(contraction, grouped_contraction);
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let primitive = GaussianPrimitive::new(0.5, 2.0);
+        let close_primitive = GaussianPrimitive::new(0.5 + 1e-12, 2.0 - 1e-12);
+        assert!(primitive.approx_eq(&close_primitive, 1e-9));
+        assert!(!primitive.approx_eq(&close_primitive, 1e-15));
+        assert!(!primitive.approx_eq(&GaussianPrimitive::new(0.5, 2.1), 1e-9));
+        assert!(GaussianPrimitive::new(0.0, 1.0).approx_eq(&GaussianPrimitive::new(0.0, 1.0), 0.0));
+
+        let mut contraction = SegmentedContraction::new();
+        contraction.add_primitive(primitive).add(0.3, 0.4);
+        let mut close_contraction = SegmentedContraction::new();
+        close_contraction
+            .add_primitive(close_primitive)
+            .add(0.3, 0.4);
+        assert!(contraction.approx_eq(&close_contraction, 1e-9));
+
+        close_contraction.add(0.1, 0.1);
+        assert!(!contraction.approx_eq(&close_contraction, 1e-9));
+    }
+
+    #[test]
+    fn test_even_tempered() {
+        let contraction = SegmentedContraction::even_tempered(AngularMomentum::P, 0.1, 3.0, 5);
+        assert_eq!(contraction.get_num_primitives(), 5);
+        assert_abs_diff_eq!(contraction.get(0).unwrap().exponental(), 0.1);
+        for (a, b) in contraction.iter().zip(contraction.iter().skip(1)) {
+            assert_abs_diff_eq!(b.exponental() / a.exponental(), 3.0, epsilon = 1e-12);
+            assert_eq!(b.coefficient(), 1.0);
+        }
+        assert_eq!(
+            SegmentedContraction::even_tempered(AngularMomentum::S, 0.1, 3.0, 0)
+                .get_num_primitives(),
+            0
+        );
     }
 }
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use super::{atomic_basis_set::AtomicBasisSet, basis_set_assignment::BasisSetAssignmentType};
+
+/// Basis sets of a whole file keyed by their assignment, e.g. as returned by
+/// `read_all_basis_sets`
+#[derive(Clone, Default)]
+pub struct BasisSetLibrary(HashMap<BasisSetAssignmentType, AtomicBasisSet>);
+
+impl BasisSetLibrary {
+    pub fn new() -> Self {
+        BasisSetLibrary(HashMap::new())
+    }
+
+    /// Adds the basis set, replacing any earlier one with the same assignment
+    pub fn insert(
+        &mut self,
+        assignment: BasisSetAssignmentType,
+        basis_set: AtomicBasisSet,
+    ) -> &mut Self {
+        self.0.insert(assignment, basis_set);
+        self
+    }
+
+    pub fn get(&self, assignment: &BasisSetAssignmentType) -> Option<&AtomicBasisSet> {
+        self.0.get(assignment)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Approximate number of bytes the library allocates on the heap, for the table and every
+    /// basis set in it
+    pub fn memory_footprint(&self) -> usize {
+        self.0.capacity() * std::mem::size_of::<(BasisSetAssignmentType, AtomicBasisSet)>()
+            + self
+                .0
+                .iter()
+                .map(|(assignment, basis_set)| {
+                    let assignment_bytes = match assignment {
+                        BasisSetAssignmentType::Atom(atom)
+                        | BasisSetAssignmentType::Ghost(atom) => atom.capacity(),
+                        _ => 0,
+                    };
+                    assignment_bytes + basis_set.memory_footprint()
+                })
+                .sum::<usize>()
+    }
+}
+
+impl From<Vec<(BasisSetAssignmentType, AtomicBasisSet)>> for BasisSetLibrary {
+    fn from(entries: Vec<(BasisSetAssignmentType, AtomicBasisSet)>) -> Self {
+        let mut library = BasisSetLibrary::new();
+        for (assignment, basis_set) in entries {
+            library.insert(assignment, basis_set);
+        }
+        library
+    }
+}
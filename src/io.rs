@@ -1,2 +1,28 @@
+mod detect;
+#[cfg(any(feature = "gamess", feature = "orca"))]
+mod gamess_shell;
+mod parse_error;
+mod writer;
+
+pub use detect::{detect_format, BasisFormat};
+pub use parse_error::BasisSetParseError;
+pub use writer::BasisSetWriter;
+
+#[cfg(feature = "bse_json")]
+pub mod bse_json;
+#[cfg(feature = "dalton")]
+pub mod dalton;
+#[cfg(feature = "gamess")]
+pub mod gamess;
 #[cfg(feature = "gaussian")]
 pub mod gaussian;
+#[cfg(feature = "genbas")]
+pub mod genbas;
+#[cfg(feature = "nwchem")]
+pub mod nwchem;
+#[cfg(feature = "orca")]
+pub mod orca;
+#[cfg(feature = "psi4")]
+pub mod psi4;
+#[cfg(feature = "turbomole")]
+pub mod turbomole;
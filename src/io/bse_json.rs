@@ -0,0 +1,185 @@
+use std::error::Error;
+
+use serde::Deserialize;
+
+use super::BasisSetParseError;
+use crate::details::{angular_momentum::AngularMomentum, atomic_basis_set::AtomicBasisSet};
+
+// Only the fields needed to build the basis set are read, the rest of the schema is ignored
+#[derive(Deserialize)]
+struct BseElement {
+    electron_shells: Vec<BseElectronShell>,
+}
+
+#[derive(Deserialize)]
+struct BseElectronShell {
+    angular_momentum: Vec<usize>,
+    // Values are kept as strings in the schema to preserve their precision
+    exponents: Vec<String>,
+    coefficients: Vec<Vec<String>>,
+}
+
+fn parse_bse_float(value: &str, index: usize) -> Result<f64, BasisSetParseError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| BasisSetParseError::BadShell {
+            index,
+            text: format!("bad value '{}'", value),
+        })
+}
+
+fn add_electron_shell(
+    basis_set: &mut AtomicBasisSet,
+    shell: &BseElectronShell,
+    index: usize,
+) -> Result<(), BasisSetParseError> {
+    let bad_shell = |text: &str| BasisSetParseError::BadShell {
+        index,
+        text: text.to_string(),
+    };
+
+    let angular_momentums = shell
+        .angular_momentum
+        .iter()
+        .map(|l| AngularMomentum::try_from(*l))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| bad_shell(&err.to_string()))?;
+    // Either one coefficient row per angular momentum (SP), or a general contraction
+    if angular_momentums.is_empty()
+        || (angular_momentums.len() > 1 && angular_momentums.len() != shell.coefficients.len())
+    {
+        return Err(bad_shell(
+            "number of angular momentums does not match the coefficients",
+        ));
+    }
+    if shell
+        .coefficients
+        .iter()
+        .any(|coefficients| coefficients.len() != shell.exponents.len())
+    {
+        return Err(bad_shell(
+            "number of coefficients does not match the exponents",
+        ));
+    }
+
+    // Same layout as the rows of a basis set file, exponental followed by the coefficients
+    let mut rows = vec![];
+    for (primitive_index, exponent) in shell.exponents.iter().enumerate() {
+        let mut row = vec![parse_bse_float(exponent, index)?];
+        for coefficients in &shell.coefficients {
+            row.push(parse_bse_float(&coefficients[primitive_index], index)?);
+        }
+        rows.push(row);
+    }
+    basis_set
+        .add_combined_shell(&angular_momentums, &rows)
+        .map_err(|err| bad_shell(&err.to_string()))?;
+    Ok(())
+}
+
+/// Reads the entry of a single element in the Basis Set Exchange JSON schema, i.e. the object
+/// holding `electron_shells`
+pub fn read_basis_set(reader: &mut dyn std::io::Read) -> Result<AtomicBasisSet, Box<dyn Error>> {
+    let element: BseElement = serde_json::from_reader(reader)?;
+
+    let mut basis_set = AtomicBasisSet::new();
+    for (index, shell) in element.electron_shells.iter().enumerate() {
+        add_electron_shell(&mut basis_set, shell, index)?;
+    }
+    Ok(basis_set)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use crate::details::angular_momentum::AngularMomentum;
+
+    use super::read_basis_set;
+
+    // 6-31G** for H, trimmed from the Basis Set Exchange
+    const HYDROGEN_BSE_JSON: &str = r#"{
+        "electron_shells": [
+            {
+                "function_type": "gto",
+                "region": "valence",
+                "angular_momentum": [0],
+                "exponents": ["0.1873113696E+02", "0.2825394365E+01", "0.6401216923E+00"],
+                "coefficients": [["0.3349460434E-01", "0.2347269535E+00", "0.8137573261E+00"]]
+            },
+            {
+                "function_type": "gto",
+                "region": "valence",
+                "angular_momentum": [0],
+                "exponents": ["0.1612777588E+00"],
+                "coefficients": [["1.0000000"]]
+            },
+            {
+                "function_type": "gto",
+                "region": "polarization",
+                "angular_momentum": [1],
+                "exponents": ["1.1000000"],
+                "coefficients": [["1.0000000"]]
+            }
+        ],
+        "references": []
+    }"#;
+
+    #[test]
+    fn test_read_hydrogen() {
+        let basis_set = read_basis_set(&mut HYDROGEN_BSE_JSON.as_bytes()).unwrap();
+        assert_eq!(basis_set.get_num_contracted_functions(), 3);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 5);
+        assert_eq!(basis_set.get_highest_angular_momentum(), AngularMomentum::P);
+
+        let (angular_momentum, contraction) = basis_set.into_iter().next().unwrap();
+        assert_eq!(angular_momentum, AngularMomentum::S);
+        assert_abs_diff_eq!(contraction.get(1).unwrap().exponental(), 2.825394365);
+        assert_abs_diff_eq!(contraction.get(1).unwrap().coefficient(), 0.2347269535);
+    }
+
+    #[test]
+    fn test_read_sp_and_general_contraction() {
+        let json = r#"{
+            "electron_shells": [
+                {
+                    "angular_momentum": [0, 1],
+                    "exponents": ["3.0", "0.5"],
+                    "coefficients": [["0.1", "0.9"], ["0.2", "0.8"]]
+                },
+                {
+                    "angular_momentum": [2],
+                    "exponents": ["2.0", "0.4"],
+                    "coefficients": [["0.6", "0.5"], ["0.0", "1.0"]]
+                }
+            ]
+        }"#;
+
+        let basis_set = read_basis_set(&mut json.as_bytes()).unwrap();
+        let contractions: Vec<_> = basis_set.into_iter().collect();
+        assert_eq!(contractions.len(), 4);
+        assert_eq!(contractions[0].0, AngularMomentum::S);
+        assert_eq!(contractions[1].0, AngularMomentum::P);
+        assert_abs_diff_eq!(contractions[1].1.get(0).unwrap().coefficient(), 0.2);
+        assert_abs_diff_eq!(contractions[1].1.get(1).unwrap().exponental(), 0.5);
+        assert_eq!(contractions[2].0, AngularMomentum::D);
+        assert_eq!(contractions[3].0, AngularMomentum::D);
+        assert_abs_diff_eq!(contractions[3].1.get(1).unwrap().coefficient(), 1.0);
+    }
+
+    #[test]
+    fn test_read_bad_shell() {
+        let json = r#"{
+            "electron_shells": [
+                {
+                    "angular_momentum": [0],
+                    "exponents": ["3.0", "0.5"],
+                    "coefficients": [["0.1"]]
+                }
+            ]
+        }"#;
+
+        assert!(read_basis_set(&mut json.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+use std::error::Error;
+
+use super::BasisSetParseError;
+use crate::details::{
+    angular_momentum::AngularMomentum, atomic_basis_set::AtomicBasisSet,
+    basis_set_assignment::BasisSetAssignmentType,
+};
+
+/// Shell being read, complete once its last row holds every contracted function
+struct PendingShell {
+    num_primitives: usize,
+    num_contracted_functions: usize,
+    rows: Vec<Vec<f64>>,
+}
+
+fn parse_element_line(
+    line: &str,
+    line_number: usize,
+) -> Result<BasisSetAssignmentType, BasisSetParseError> {
+    // "a" followed by the atomic number, e.g. "a 6"
+    line.split_whitespace()
+        .nth(1)
+        .and_then(|value| value.parse::<usize>().ok())
+        .and_then(BasisSetAssignmentType::from_atomic_number)
+        .ok_or_else(|| BasisSetParseError::BadDeclaration {
+            line: line_number,
+            text: line.to_string(),
+        })
+}
+
+fn parse_shell_header(line: &str, line_number: usize) -> Result<PendingShell, BasisSetParseError> {
+    // Number of primitives and of contracted functions, e.g. "    4    2    0"
+    let values = line
+        .split_whitespace()
+        .map(|value| value.parse::<usize>())
+        .collect::<Result<Vec<usize>, _>>()
+        .ok()
+        .filter(|values| values.len() >= 2)
+        .ok_or_else(|| BasisSetParseError::BadCgtoHeader {
+            line: line_number,
+            text: line.to_string(),
+        })?;
+    Ok(PendingShell {
+        num_primitives: values[0],
+        num_contracted_functions: values[1],
+        rows: vec![],
+    })
+}
+
+/// Reads a Dalton basis set file, returning one basis set per element. Elements start with
+/// `a` and their atomic number, e.g. `a 6`. Shells follow in increasing angular momentum, each
+/// with a header holding the numbers of primitives and contracted functions, then one row per
+/// primitive of the exponental followed by the coefficient matrix row. Long rows continue on
+/// the next lines. Lines starting with `!` or `$` are comments.
+pub fn read_basis_set(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let mut basis_sets: Vec<(BasisSetAssignmentType, AtomicBasisSet)> = vec![];
+    // Angular momentum of the next shell of the current element
+    let mut angular_momentum_num = 0;
+    let mut pending_shell: Option<PendingShell> = None;
+
+    for (index, item) in stream.enumerate() {
+        let line_number = index + 1;
+        let line = item.map_err(BasisSetParseError::Io)?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('!') || trimmed.starts_with('$') {
+            continue;
+        }
+
+        if let Some(shell) = pending_shell.as_mut() {
+            let bad_float = || BasisSetParseError::BadFloat {
+                line: line_number,
+                text: line.clone(),
+            };
+            let values = trimmed
+                .split_whitespace()
+                .map(|value| value.parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| bad_float())?;
+            let row_len = shell.num_contracted_functions + 1;
+            match shell.rows.last_mut() {
+                Some(row) if row.len() < row_len => row.extend(values),
+                _ => shell.rows.push(values),
+            }
+            if shell.rows.last().unwrap().len() > row_len {
+                return Err(Box::new(bad_float()));
+            }
+
+            if shell.rows.len() == shell.num_primitives
+                && shell.rows.last().unwrap().len() == row_len
+            {
+                let shell = pending_shell.take().unwrap();
+                let angular_momentum =
+                    AngularMomentum::try_from(angular_momentum_num).map_err(|_| {
+                        BasisSetParseError::UnknownAngularMomentum {
+                            line: line_number,
+                            text: line.clone(),
+                        }
+                    })?;
+                let (_, basis_set) = basis_sets.last_mut().unwrap();
+                basis_set.add_combined_shell(&[angular_momentum], &shell.rows)?;
+                angular_momentum_num += 1;
+            }
+        } else if trimmed.starts_with(['a', 'A']) {
+            let assignment = parse_element_line(trimmed, line_number)?;
+            basis_sets.push((assignment, AtomicBasisSet::new()));
+            angular_momentum_num = 0;
+        } else if basis_sets.is_empty() {
+            return Err(Box::new(BasisSetParseError::BadDeclaration {
+                line: line_number,
+                text: line,
+            }));
+        } else {
+            let shell = parse_shell_header(trimmed, line_number)?;
+            if shell.num_primitives > 0 {
+                pending_shell = Some(shell);
+            } else {
+                angular_momentum_num += 1;
+            }
+        }
+    }
+
+    if pending_shell.is_some() {
+        return Err(Box::new(BasisSetParseError::UnexpectedEof));
+    }
+    Ok(basis_sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::details::{
+        angular_momentum::AngularMomentum, basis_set_assignment::BasisSetAssignmentType,
+    };
+    use crate::io::BasisSetParseError;
+
+    use super::read_basis_set;
+
+    // 6-31G** for H, from the Basis Set Exchange
+    const HYDROGEN_BASIS_SET: &str = "! Basis set: 6-31G**
+a 1
+! H       (4s,1p) -> [2s,1p]
+! H       (4s,1p) -> [2s,1p]
+    4    2
+     18.7311370           0.03349460             0.0000000
+      2.8253937           0.23472695             0.0000000
+      0.6401217           0.81375733             0.0000000
+      0.1612778           0.0000000              1.0000000
+    1    1
+      1.1000000           1.0000000
+";
+
+    #[test]
+    fn test_read_hydrogen_basis_set() {
+        let basis_sets = read_basis_set(&mut Cursor::new(HYDROGEN_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_sets.len(), 1);
+        let (assignment, basis_set) = &basis_sets[0];
+        assert_eq!(*assignment, BasisSetAssignmentType::Atom("H".to_string()));
+        assert_eq!(basis_set.get_num_contracted_functions(), 3);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 9);
+
+        let contractions: Vec<_> = basis_set.into_iter().collect();
+        assert_eq!(contractions[0].0, AngularMomentum::S);
+        assert_eq!(contractions[1].0, AngularMomentum::S);
+        assert_abs_diff_eq!(contractions[0].1.get(2).unwrap().exponental(), 0.6401217);
+        assert_abs_diff_eq!(contractions[0].1.get(2).unwrap().coefficient(), 0.81375733);
+        assert_abs_diff_eq!(contractions[1].1.get(3).unwrap().coefficient(), 1.0);
+        assert_eq!(contractions[2].0, AngularMomentum::P);
+        assert_abs_diff_eq!(contractions[2].1.get(0).unwrap().exponental(), 1.1);
+    }
+
+    #[test]
+    fn test_read_wrapped_rows() {
+        let input = "a 6\n    2    3\n  3.0  0.1  0.2\n  0.3\n  0.5  0.9  0.8  0.7\n";
+        let basis_sets = read_basis_set(&mut Cursor::new(input).lines()).unwrap();
+        let (assignment, basis_set) = &basis_sets[0];
+        assert_eq!(*assignment, BasisSetAssignmentType::Atom("C".to_string()));
+        assert_eq!(basis_set.get_num_contracted_functions(), 3);
+
+        let contractions: Vec<_> = basis_set.into_iter().collect();
+        assert_abs_diff_eq!(contractions[2].1.get(0).unwrap().coefficient(), 0.3);
+        assert_abs_diff_eq!(contractions[2].1.get(1).unwrap().exponental(), 0.5);
+    }
+
+    #[test]
+    fn test_read_truncated_shell() {
+        let error = read_basis_set(&mut Cursor::new("a 1\n    2    1\n  3.0  0.5\n").lines())
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::UnexpectedEof)
+        ));
+    }
+}
@@ -0,0 +1,99 @@
+/// File format of a basis set, as told by `detect_format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BasisFormat {
+    // Gaussian94, atom blocks closed by `****`
+    Gaussian,
+    // NWChem `BASIS "ao basis"` blocks
+    NwChem,
+    // Turbomole `$basis` data group
+    Turbomole,
+    // JSON of the Basis Set Exchange
+    BseJson,
+    // None of the markers was found
+    Unknown,
+}
+
+// Shell header of the Gaussian94 format, letters followed by the number of primitives and
+// optionally a scale factor, e.g. `SP   3   1.00`
+fn is_gaussian_shell_header(line: &str) -> bool {
+    let split: Vec<&str> = line.split_whitespace().collect();
+    matches!(split.len(), 2 | 3)
+        && split[0].chars().all(|ch| "SPDFGHIKLspdfghikl".contains(ch))
+        && split[1].parse::<usize>().is_ok()
+        && split
+            .get(2)
+            .is_none_or(|value| value.parse::<f64>().is_ok())
+}
+
+/// Guesses the format of a basis set file from its first lines, a handful of them is enough.
+/// Blank lines and `!` or `#` comments are skipped. The markers are a leading `{` for BSE
+/// JSON, `$basis` for Turbomole, `BASIS "ao basis"` for NWChem and `****` or a shell header
+/// such as `S    6   1.00` for Gaussian94.
+pub fn detect_format(first_lines: &[String]) -> BasisFormat {
+    let lines: Vec<&str> = first_lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('!') && !line.starts_with('#'))
+        .collect();
+
+    if lines.first().is_some_and(|line| line.starts_with('{')) {
+        return BasisFormat::BseJson;
+    }
+    if lines
+        .iter()
+        .any(|line| line.to_ascii_lowercase().starts_with("$basis"))
+    {
+        return BasisFormat::Turbomole;
+    }
+    if lines.iter().any(|line| {
+        let mut split = line.split_whitespace();
+        split
+            .next()
+            .is_some_and(|keyword| keyword.eq_ignore_ascii_case("BASIS"))
+            && split.next().is_some_and(|name| name.starts_with('"'))
+    }) {
+        return BasisFormat::NwChem;
+    }
+    if lines.iter().any(|line| {
+        (line.len() >= 2 && line.chars().all(|ch| ch == '*')) || is_gaussian_shell_header(line)
+    }) {
+        return BasisFormat::Gaussian;
+    }
+    BasisFormat::Unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_format, BasisFormat};
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_detect_format() {
+        for (text, expected) in [
+            (
+                "! Basis Set Exchange\n\nC     0\nS    6   1.00\n   4563.240   0.00196665\n",
+                BasisFormat::Gaussian,
+            ),
+            ("O     0\n****\n", BasisFormat::Gaussian),
+            (
+                "#BASIS SET: (4s) -> [2s]\nBASIS \"ao basis\" SPHERICAL PRINT\nH    S\n",
+                BasisFormat::NwChem,
+            ),
+            (
+                "$basis\n*\nh def-SV(P)\n# hydrogen  (4s) / [2s]\n*\n   3  s\n",
+                BasisFormat::Turbomole,
+            ),
+            (
+                "{\n    \"molssi_bse_schema\": {\n        \"schema_type\": \"complete\",\n",
+                BasisFormat::BseJson,
+            ),
+            ("", BasisFormat::Unknown),
+            ("Hello there\n  1.0  2.0\n", BasisFormat::Unknown),
+        ] {
+            assert_eq!(detect_format(&lines(text)), expected, "{}", text);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::error::Error;
+
+use super::{
+    gamess_shell::{parse_primitive_row, parse_shell_header, PendingShell},
+    BasisSetParseError,
+};
+use crate::details::{
+    atomic_basis_set::AtomicBasisSet, basis_set_assignment::BasisSetAssignmentType,
+};
+
+/// Reads basis sets in the GAMESS(US) layout written by the Basis Set Exchange, returning one
+/// basis set per atom. Every atom starts with its name, followed by shells of a `S   6` style
+/// header and rows of primitive index, exponental and coefficients, and ends with a blank line.
+/// `L` shells hold an S and a P coefficient. Only the Basis Set Exchange export is supported:
+/// the `$DATA` group of a GAMESS input starts with a title and a symmetry line, which would be
+/// taken as atom names.
+pub fn read_basis_set(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let mut basis_sets: Vec<(BasisSetAssignmentType, AtomicBasisSet)> = vec![];
+    let mut in_atom = false;
+    let mut pending_shell: Option<PendingShell> = None;
+
+    for (index, item) in stream.enumerate() {
+        let line_number = index + 1;
+        let line = item.map_err(BasisSetParseError::Io)?;
+        let trimmed = line.trim();
+        if trimmed.starts_with('!') || trimmed.starts_with('$') {
+            continue;
+        }
+
+        if let Some(shell) = pending_shell.as_mut() {
+            // An exponental and one coefficient per angular momentum
+            let num_columns = shell.angular_momentums.len() + 1;
+            shell
+                .rows
+                .push(parse_primitive_row(trimmed, line_number, num_columns)?);
+            if shell.rows.len() == shell.num_primitives {
+                let shell = pending_shell.take().unwrap();
+                let (_, basis_set) = basis_sets.last_mut().unwrap();
+                basis_set.add_combined_shell(&shell.angular_momentums, &shell.rows)?;
+            }
+        } else if trimmed.is_empty() {
+            in_atom = false;
+        } else if !in_atom {
+            let name = trimmed.split_whitespace().next().unwrap_or_default();
+            basis_sets.push((
+                BasisSetAssignmentType::Atom(name.to_string()),
+                AtomicBasisSet::new(),
+            ));
+            in_atom = true;
+        } else {
+            let shell = parse_shell_header(trimmed, line_number)?;
+            if shell.num_primitives > 0 {
+                pending_shell = Some(shell);
+            }
+        }
+    }
+
+    if pending_shell.is_some() {
+        return Err(Box::new(BasisSetParseError::UnexpectedEof));
+    }
+    Ok(basis_sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::details::{
+        angular_momentum::AngularMomentum, basis_set_assignment::BasisSetAssignmentType,
+    };
+    use crate::io::BasisSetParseError;
+
+    use super::read_basis_set;
+
+    // 6-31G for C, from the Basis Set Exchange
+    const CARBON_BASIS_SET: &str = "$DATA
+CARBON
+S   6
+  1      3047.5249000              0.0018347
+  2       457.3695100              0.0140373
+  3       103.9486900              0.0688426
+  4        29.2101550              0.2321844
+  5         9.2866630              0.4679413
+  6         3.1639270              0.3623120
+L   3
+  1         7.8682724             -0.1193324              0.0689991
+  2         1.8812885             -0.1608542              0.3164240
+  3         0.5442493              1.1434564              0.7443083
+L   1
+  1         0.1687144              1.0000000              1.0000000
+
+$END
+";
+
+    #[test]
+    fn test_read_carbon_basis_set() {
+        let basis_sets = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_sets.len(), 1);
+        let (assignment, basis_set) = &basis_sets[0];
+        assert_eq!(
+            *assignment,
+            BasisSetAssignmentType::Atom("CARBON".to_string())
+        );
+        assert_eq!(basis_set.get_num_contracted_functions(), 5);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 14);
+        assert_eq!(basis_set.get_highest_angular_momentum(), AngularMomentum::P);
+
+        let contractions: Vec<_> = basis_set.into_iter().collect();
+        assert_eq!(contractions[0].0, AngularMomentum::S);
+        let exponentals: Vec<f64> = contractions[0]
+            .1
+            .iter()
+            .map(|primitive| primitive.exponental())
+            .collect();
+        assert_eq!(exponentals.len(), 6);
+        assert_abs_diff_eq!(exponentals[0], 3047.5249);
+        assert_abs_diff_eq!(exponentals[5], 3.163927);
+        assert_abs_diff_eq!(contractions[0].1.get(5).unwrap().coefficient(), 0.362312);
+
+        // The P part of the first L shell
+        assert_eq!(contractions[3].0, AngularMomentum::P);
+        assert_abs_diff_eq!(contractions[3].1.get(0).unwrap().exponental(), 7.8682724);
+        assert_abs_diff_eq!(contractions[3].1.get(0).unwrap().coefficient(), 0.0689991);
+    }
+
+    #[test]
+    fn test_read_truncated_shell() {
+        let error = read_basis_set(&mut Cursor::new("C\nS   2\n  1   3.0   0.5\n").lines())
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn test_read_bad_column_count() {
+        for input in [
+            "C\nL   1\n  1   3.0   0.5\n",
+            "C\nS   1\n  1   3.0   0.5   0.2\n",
+        ] {
+            let error = read_basis_set(&mut Cursor::new(input).lines())
+                .err()
+                .unwrap();
+            assert!(matches!(
+                error.downcast_ref::<BasisSetParseError>(),
+                Some(BasisSetParseError::BadColumnCount { line: 3, .. })
+            ));
+        }
+    }
+}
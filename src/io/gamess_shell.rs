@@ -0,0 +1,78 @@
+use super::BasisSetParseError;
+use crate::details::angular_momentum::AngularMomentum;
+
+/// Shell of the GAMESS layout, which ORCA shares, being read. It is complete once it holds as
+/// many rows as its header has primitives.
+pub(crate) struct PendingShell {
+    pub(crate) angular_momentums: Vec<AngularMomentum>,
+    pub(crate) num_primitives: usize,
+    pub(crate) rows: Vec<Vec<f64>>,
+}
+
+pub(crate) fn parse_shell_header(
+    line: &str,
+    line_number: usize,
+) -> Result<PendingShell, BasisSetParseError> {
+    // Shell letter and number of primitives, optionally followed by the scale factor GAMESS
+    // allows, e.g. "L   3"
+    let split: Vec<&str> = line.split_whitespace().collect();
+    if !matches!(split.len(), 2 | 3) || split[0].chars().count() != 1 {
+        return Err(BasisSetParseError::BadCgtoHeader {
+            line: line_number,
+            text: line.to_string(),
+        });
+    }
+    let num_primitives =
+        split[1]
+            .parse::<usize>()
+            .map_err(|_| BasisSetParseError::BadCgtoHeader {
+                line: line_number,
+                text: line.to_string(),
+            })?;
+    // Combined SP shells are written as L
+    let angular_momentums = match split[0] {
+        "L" | "l" => vec![AngularMomentum::S, AngularMomentum::P],
+        letter => letter
+            .chars()
+            .map(AngularMomentum::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| BasisSetParseError::UnknownAngularMomentum {
+                line: line_number,
+                text: line.to_string(),
+            })?,
+    };
+    Ok(PendingShell {
+        angular_momentums,
+        num_primitives,
+        rows: vec![],
+    })
+}
+
+/// Exponental and coefficients of a primitive row, which needs exactly num_columns of them
+pub(crate) fn parse_primitive_row(
+    line: &str,
+    line_number: usize,
+    num_columns: usize,
+) -> Result<Vec<f64>, BasisSetParseError> {
+    let bad_float = || BasisSetParseError::BadFloat {
+        line: line_number,
+        text: line.to_string(),
+    };
+    // The leading 1-based primitive index is not part of the data
+    let mut split = line.split_whitespace();
+    split
+        .next()
+        .and_then(|index| index.parse::<usize>().ok())
+        .ok_or_else(bad_float)?;
+    let row = split
+        .map(|value| value.parse::<f64>())
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| bad_float())?;
+    if row.len() != num_columns {
+        return Err(BasisSetParseError::BadColumnCount {
+            line: line_number,
+            text: line.to_string(),
+        });
+    }
+    Ok(row)
+}
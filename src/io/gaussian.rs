@@ -1,234 +1,1050 @@
-use std::error::Error;
+use std::{
+    collections::HashSet,
+    error::Error,
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
 
+pub use super::BasisSetParseError;
+use super::{writer::check_angular_momentums, BasisSetWriter};
+pub use crate::details::basis_set_assignment::BasisSetAssignmentType;
 use crate::details::{
-    angular_momentum::AngularMomentum, atomic_basis_set::AtomicBasisSet,
-    gaussian_exp::SegmentedContraction,
+    angular_momentum::AngularMomentum,
+    atomic_basis_set::{AtomicBasisSet, BasisSetValidationError},
+    ecp::{EcpTerm, EffectiveCorePotential},
+    gaussian_exp::has_duplicate_values,
 };
 
-#[derive(Debug)]
-pub struct BasisSetParseError(String);
+/// Options of the basis set reader, the default matches `read_basis_set`
+#[derive(Clone)]
+pub struct ReaderConfig {
+    /// Rejects shells repeating an exponental within this relative tolerance
+    pub duplicate_exponent_tolerance: Option<f64>,
+    /// Rejects atoms that are not elements of the periodic table, and returns the element
+    /// symbols capitalized canonically
+    pub validate_element_symbols: bool,
+    /// Lines starting with any of these prefixes are comments, `!` by default. The prefixes are
+    /// matched from the first column, so e.g. `*` still leaves `****` terminators alone.
+    pub comment_prefixes: Vec<String>,
+    /// Rejects an atom block cut off by the end of the stream rather than closed by `****`
+    pub require_terminator: bool,
+    /// Accepts primitive rows wrapped over several lines, e.g. long general contraction rows.
+    /// The values of a shell are read up to the next line not starting with a number, then
+    /// split evenly between its primitives.
+    pub continuation_rows: bool,
+}
 
-impl BasisSetParseError {
-    fn new(message: &str) -> Self {
-        BasisSetParseError(message.to_string())
+impl Default for ReaderConfig {
+    fn default() -> Self {
+        ReaderConfig {
+            duplicate_exponent_tolerance: None,
+            validate_element_symbols: false,
+            comment_prefixes: vec!["!".to_string()],
+            require_terminator: false,
+            continuation_rows: false,
+        }
     }
 }
 
-impl std::fmt::Display for BasisSetParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}: {}", self.to_string(), self.0)
-    }
+/// Whether the line ends an atom block, i.e. only holds stars, `****` in well-formed files
+fn is_terminator(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() >= 2 && trimmed.chars().all(|ch| ch == '*')
+}
+
+/// Outcome of reading the next meaningful line of a basis set file
+#[derive(Debug, PartialEq)]
+enum BasisSetLine {
+    Line(String),
+    // A `****` line closing an atom block
+    Terminator,
+    // The end of the stream
+    Eof,
 }
 
-impl Error for BasisSetParseError {
-    fn description(&self) -> &str {
-        "Failed to parse Gaussian basis set information"
+impl BasisSetLine {
+    /// The line, `None` for both the terminator and the end of the stream
+    fn into_line(self) -> Option<String> {
+        match self {
+            BasisSetLine::Line(line) => Some(line),
+            BasisSetLine::Terminator | BasisSetLine::Eof => None,
+        }
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum BasisSetAssignmentType {
-    // Basis set for a type of atom
-    Atom(String),
-    // Basis set for a particle in the molecule, specified by the index starting with 0
-    ParticleIndex(i32),
+/// Line stream of a basis set file, keeping track of the line number for error reporting.
+/// Generic over the stream so `read_basis_set_from` avoids dynamic dispatch per line.
+struct BasisSetLineReader<'a, I: ?Sized> {
+    stream: &'a mut I,
+    config: &'a ReaderConfig,
+    // Number of the last line read from the stream, starting with 1
+    line_number: usize,
+    // Text of the skipped comment lines, only collected when asked for
+    comments: Option<Vec<String>>,
+    // Line read ahead and put back, returned by the next read
+    pushed_back: Option<BasisSetLine>,
 }
 
-fn read_single_basis_set_line(
-    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
-) -> Result<Option<String>, Box<dyn Error>> {
-    let mut option_item = stream.next();
-    while let Some(item) = option_item {
-        match item {
-            Ok(string) => {
-                if string.starts_with("!") || string.trim().len() == 0 {
-                    option_item = stream.next();
-                    continue;
-                }
-                if string.starts_with("****") {
-                    return Ok(None);
+impl<'a, I> BasisSetLineReader<'a, I>
+where
+    I: Iterator<Item = Result<String, std::io::Error>> + ?Sized,
+{
+    fn new(stream: &'a mut I, config: &'a ReaderConfig) -> Self {
+        BasisSetLineReader {
+            stream,
+            config,
+            line_number: 0,
+            comments: None,
+            pushed_back: None,
+        }
+    }
+
+    /// Returns the next meaningful line, skipping comments and blank lines, or tells the
+    /// `****` terminator from the end of the stream
+    fn read_single_basis_set_line(&mut self) -> Result<BasisSetLine, BasisSetParseError> {
+        if let Some(line) = self.pushed_back.take() {
+            return Ok(line);
+        }
+        for item in &mut self.stream {
+            self.line_number += 1;
+            let string = item.map_err(BasisSetParseError::Io)?;
+            if is_terminator(&string) {
+                return Ok(BasisSetLine::Terminator);
+            }
+            let comment = self
+                .config
+                .comment_prefixes
+                .iter()
+                .find_map(|prefix| string.strip_prefix(prefix.as_str()));
+            if let Some(comment) = comment {
+                if let Some(comments) = self.comments.as_mut() {
+                    comments.push(comment.trim().to_string());
                 }
-                return Ok(Some(string));
+                continue;
+            }
+            if string.trim().is_empty() {
+                continue;
             }
-            Err(error) => {
-                return Err(Box::new(BasisSetParseError(error.to_string())));
+            return Ok(BasisSetLine::Line(string));
+        }
+        Ok(BasisSetLine::Eof)
+    }
+
+    /// Skips the rest of the current atom block without parsing it, up to its terminator
+    fn skip_basis_set_block(&mut self) -> Result<(), BasisSetParseError> {
+        for item in &mut self.stream {
+            self.line_number += 1;
+            if is_terminator(&item.map_err(BasisSetParseError::Io)?) {
+                break;
             }
         }
+        Ok(())
+    }
+}
+
+// Markers of a ghost atom, alone or joined to the element by a dash, e.g. `C-Bq` or `Ghost-C`
+const GHOST_MARKERS: [&str; 3] = ["Bq", "Gh", "Ghost"];
+
+// Element of a ghost atom declaration, empty for a bare marker, `None` for a regular atom
+fn parse_ghost(value: &str) -> Option<&str> {
+    let is_marker = |token: &str| {
+        GHOST_MARKERS
+            .iter()
+            .any(|marker| marker.eq_ignore_ascii_case(token))
+    };
+    if is_marker(value) {
+        return Some("");
+    }
+    match value.split_once('-') {
+        Some((atom, marker)) | Some((marker, atom)) if is_marker(marker) && !atom.is_empty() => {
+            Some(atom)
+        }
+        _ => None,
     }
-    return Ok(None);
 }
 
 fn parse_basis_set_first_line(
     first_line: &Option<String>,
-) -> Result<BasisSetAssignmentType, Box<dyn Error>> {
+    line_number: usize,
+) -> Result<BasisSetAssignmentType, BasisSetParseError> {
     match first_line {
-        None => Err(Box::new(BasisSetParseError::new("Bad basis set"))),
+        None => Err(BasisSetParseError::UnexpectedEof),
         Some(declaration_line) => {
-            let mut split = declaration_line.split_whitespace();
-            let value = split
-                .next()
-                .ok_or_else(|| BasisSetParseError::new("Expect atom/particle index"))?;
-            let particle_index = value.parse::<i32>();
-            match particle_index {
-                Err(_) => Ok(BasisSetAssignmentType::Atom(value.to_string())),
-                Ok(v) => Ok(BasisSetAssignmentType::ParticleIndex(v)),
+            let bad_declaration = || BasisSetParseError::BadDeclaration {
+                line: line_number,
+                text: declaration_line.clone(),
+            };
+            let tokens: Vec<&str> = declaration_line.split_whitespace().take(3).collect();
+            let value = *tokens.first().ok_or_else(bad_declaration)?;
+            // Three coordinates, at least one of them written as a non-integral number, so
+            // that e.g. `0 0 1.5` is a center while `1 0` is a particle index
+            let coordinates: Vec<f64> = tokens
+                .iter()
+                .map_while(|coordinate| parse_float(coordinate).ok())
+                .collect();
+            let has_non_integral = tokens.iter().any(|token| token.parse::<i32>().is_err());
+            match coordinates.as_slice() {
+                [x, y, z] if has_non_integral => Ok(BasisSetAssignmentType::Center {
+                    x: *x,
+                    y: *y,
+                    z: *z,
+                }),
+                _ => match value.parse::<i32>() {
+                    Ok(index) => Ok(BasisSetAssignmentType::ParticleIndex(index)),
+                    // A number that is neither a particle index nor the start of a center
+                    Err(_) if parse_float(value).is_ok() => Err(bad_declaration()),
+                    Err(_) => match parse_ghost(value) {
+                        Some(atom) => Ok(BasisSetAssignmentType::Ghost(atom.to_string())),
+                        None => Ok(BasisSetAssignmentType::Atom(value.to_string())),
+                    },
+                },
             }
         }
     }
 }
 
-fn parse_cgto_first_line(line: &Option<String>) -> Result<(String, i32), Box<dyn Error>> {
+// Integer closing the declaration line, e.g. the 0 of `C 0`, taken as 0 when missing
+fn parse_declaration_flag(first_line: &Option<String>) -> i32 {
+    first_line
+        .as_deref()
+        .and_then(|declaration_line| {
+            let split: Vec<&str> = declaration_line.split_whitespace().collect();
+            match split.as_slice() {
+                [_, .., last] => last.parse::<i32>().ok(),
+                _ => None,
+            }
+        })
+        .unwrap_or(0)
+}
+
+fn parse_cgto_first_line(
+    line: &Option<String>,
+    line_number: usize,
+) -> Result<(String, usize, f64), BasisSetParseError> {
     match line {
-        None => Err(Box::new(BasisSetParseError::new(
-            "Expecting CGTO declaration",
-        ))),
+        None => Err(BasisSetParseError::UnexpectedEof),
         Some(declaration_line) => {
+            let bad_cgto_header = || BasisSetParseError::BadCgtoHeader {
+                line: line_number,
+                text: declaration_line.clone(),
+            };
+            // Angular momentum, number of Gaussian primitives and scale factor, 1 when missing
             let mut split = declaration_line.split_whitespace();
-            let angular_momentum: String = split
+            let angular_momentum = split.next().ok_or_else(bad_cgto_header)?.to_string();
+            let num_gaussian_primitives: usize = split
                 .next()
-                .ok_or_else(|| {
-                    BasisSetParseError::new(
-                        "Expecting angular momentum and number of Gaussian primitives",
-                    )
-                })?
-                .to_string();
-            match split.next() {
-                None => Err(Box::new(BasisSetParseError::new("Bad CGTO declaration"))),
-                Some(value) => {
-                    let num_gaussian_primitives: i32 = value.parse()?;
-                    Ok((angular_momentum, num_gaussian_primitives))
-                }
-            }
+                .ok_or_else(bad_cgto_header)?
+                .parse()
+                .map_err(|_| bad_cgto_header())?;
+            let scale_factor = match split.next() {
+                None => 1.0,
+                Some(value) => parse_float(value)
+                    .ok()
+                    .filter(|scale_factor| *scale_factor > 0.0)
+                    .ok_or_else(bad_cgto_header)?,
+            };
+            Ok((angular_momentum, num_gaussian_primitives, scale_factor))
         }
     }
 }
 
-fn parse_floats(line: &Option<String>) -> Result<Vec<f64>, Box<dyn Error>> {
+/// Parses a float, also accepting the Fortran `D` exponent marker, e.g. `4.563240D+03`
+fn parse_float(value: &str) -> Result<f64, std::num::ParseFloatError> {
+    value.parse::<f64>().or_else(|err| {
+        // Only a single marker between the mantissa and the exponent is swapped, anything else
+        // keeps the original error
+        match value.find(['D', 'd']) {
+            Some(index)
+                if index > 0
+                    && value[index + 1..].find(['D', 'd']).is_none()
+                    && value[..index].ends_with(|ch: char| ch.is_ascii_digit() || ch == '.')
+                    && value[index + 1..]
+                        .starts_with(|ch: char| ch.is_ascii_digit() || ch == '+' || ch == '-') =>
+            {
+                format!("{}E{}", &value[..index], &value[index + 1..]).parse::<f64>()
+            }
+            _ => Err(err),
+        }
+    })
+}
+
+fn parse_floats(line: &Option<String>, line_number: usize) -> Result<Vec<f64>, BasisSetParseError> {
     match line {
-        None => Err(Box::new(BasisSetParseError::new(
-            "Expecting line of floats",
-        ))),
-        Some(value_line) => match value_line
+        None => Err(BasisSetParseError::UnexpectedEof),
+        Some(value_line) => value_line
             .split_whitespace()
-            .map(|i| i.parse::<f64>())
-            .collect()
-        {
-            Ok(value) => Ok(value),
-            Err(err) => Err(Box::new(BasisSetParseError(err.to_string()))),
-        },
+            .map(parse_float)
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|_| BasisSetParseError::BadFloat {
+                line: line_number,
+                text: value_line.clone(),
+            }),
+    }
+}
+
+// Letters of the angular momentums of a shell, with the `L` alias of some programs spelled out
+// as the SP shell it stands for
+fn expand_shell_letters(angular_momentum_string: &str) -> &str {
+    match angular_momentum_string {
+        "L" | "l" => "SP",
+        letters => letters,
     }
 }
 
 fn add_basis_set_cgto(
     basis_set: &mut AtomicBasisSet,
     angular_momentum_string: &str,
-    data: &Vec<Vec<f64>>,
-) {
-    // The index of the exponental term
-    let mut index = 1 as usize;
+    data: &[Vec<f64>],
+) -> Result<(), BasisSetValidationError> {
+    let mut angular_momentums = vec![];
     // Angular momentum should be Ss Pp Dd Ff Gg Hh, etc.
-    for angular_momentum_ch in angular_momentum_string.as_bytes().into_iter() {
-        let angular_momentum = AngularMomentum::from(*angular_momentum_ch as char);
-        let mut segmented_contraction = SegmentedContraction::new();
-        for gaussian_index in 0..data.len() {
-            let coefficient = data[gaussian_index][0];
-            let exponental = data[gaussian_index][index];
-            segmented_contraction.add(coefficient, exponental);
+    for angular_momentum_ch in expand_shell_letters(angular_momentum_string).chars() {
+        angular_momentums.push(
+            AngularMomentum::try_from(angular_momentum_ch)
+                .map_err(|_| BasisSetValidationError::UnsupportedAngularMomentum)?,
+        );
+    }
+    basis_set.add_combined_shell(&angular_momentums, data)?;
+    Ok(())
+}
+
+/// Rows of a shell whose values may wrap over several lines, given the number of coefficient
+/// columns when known from the shell letters
+fn read_continued_rows<I>(
+    reader: &mut BasisSetLineReader<I>,
+    num_primitives: usize,
+    num_coefficients: Option<usize>,
+    declaration_line: &str,
+    declaration_line_number: usize,
+) -> Result<Vec<Vec<f64>>, BasisSetParseError>
+where
+    I: Iterator<Item = Result<String, std::io::Error>> + ?Sized,
+{
+    let mut values = vec![];
+    loop {
+        match reader.read_single_basis_set_line()? {
+            BasisSetLine::Line(line)
+                if line
+                    .split_whitespace()
+                    .next()
+                    .is_some_and(|value| parse_float(value).is_ok()) =>
+            {
+                let line_number = reader.line_number;
+                values.extend(parse_floats(&Some(line), line_number)?);
+            }
+            // The next shell header, or the end of the block
+            line => {
+                reader.pushed_back = Some(line);
+                break;
+            }
         }
-        basis_set.add_segmented_contraction(angular_momentum, segmented_contraction);
-        index += 1;
     }
+
+    let num_columns = match num_primitives {
+        0 => 0,
+        _ => values.len() / num_primitives,
+    };
+    let num_columns_expected = values.len() == num_columns * num_primitives
+        && match num_coefficients {
+            Some(num_coefficients) => num_columns == num_coefficients + 1,
+            None => num_columns >= 2 || num_primitives == 0,
+        };
+    if !num_columns_expected {
+        return Err(BasisSetParseError::BadColumnCount {
+            line: declaration_line_number,
+            text: declaration_line.to_string(),
+        });
+    }
+    Ok(values
+        .chunks(num_columns.max(1))
+        .map(|row| row.to_vec())
+        .collect())
 }
 
-pub fn read_basis_set(
-    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
-) -> Result<(BasisSetAssignmentType, AtomicBasisSet), Box<dyn Error>> {
+fn read_basis_set_block<I>(
+    first_line: &Option<String>,
+    reader: &mut BasisSetLineReader<I>,
+) -> Result<(BasisSetAssignmentType, AtomicBasisSet), BasisSetParseError>
+where
+    I: Iterator<Item = Result<String, std::io::Error>> + ?Sized,
+{
     let mut basis_set = AtomicBasisSet::new();
-    let mut read_result = read_single_basis_set_line(stream)?;
-    let basis_set_assignment_type = parse_basis_set_first_line(&read_result)?;
+    let mut basis_set_assignment_type = parse_basis_set_first_line(first_line, reader.line_number)?;
+    basis_set.set_declaration_flag(parse_declaration_flag(first_line));
+    if reader.config.validate_element_symbols {
+        basis_set_assignment_type = basis_set_assignment_type.normalize().ok_or_else(|| {
+            BasisSetParseError::BadDeclaration {
+                line: reader.line_number,
+                text: first_line.clone().unwrap_or_default(),
+            }
+        })?;
+    }
 
-    read_result = read_single_basis_set_line(stream)?;
-    while !read_result.is_none() {
-        let cgto_declaration = parse_cgto_first_line(&read_result)?;
+    let mut read_result = reader.read_single_basis_set_line()?;
+    while let BasisSetLine::Line(declaration_line) = &read_result {
+        let declaration_line_number = reader.line_number;
+        let cgto_declaration =
+            parse_cgto_first_line(&Some(declaration_line.clone()), declaration_line_number)?;
 
+        // An exponental and one coefficient per letter, or any number of coefficients for a
+        // general contraction, as long as every row agrees
+        let num_letters = expand_shell_letters(&cgto_declaration.0).chars().count();
         let mut basis_set_data = Vec::<Vec<f64>>::new();
-        for _ in 0..cgto_declaration.1 {
-            let primitive_line = read_single_basis_set_line(stream)?;
-            basis_set_data.push(parse_floats(&primitive_line)?);
+        // Wrapped rows are read all at once, otherwise one line per primitive
+        let num_single_line_rows = if reader.config.continuation_rows {
+            basis_set_data = read_continued_rows(
+                reader,
+                cgto_declaration.1,
+                (num_letters > 1).then_some(num_letters),
+                declaration_line,
+                declaration_line_number,
+            )?;
+            0
+        } else {
+            cgto_declaration.1
+        };
+        for _ in 0..num_single_line_rows {
+            let primitive_line = reader.read_single_basis_set_line()?.into_line();
+            let row = parse_floats(&primitive_line, reader.line_number)?;
+            let num_columns_expected = if num_letters > 1 {
+                row.len() == num_letters + 1
+            } else {
+                match basis_set_data.first() {
+                    Some(first_row) => row.len() == first_row.len(),
+                    None => row.len() >= 2,
+                }
+            };
+            if !num_columns_expected {
+                return Err(BasisSetParseError::BadColumnCount {
+                    line: reader.line_number,
+                    text: primitive_line.unwrap_or_default(),
+                });
+            }
+            basis_set_data.push(row);
+        }
+
+        // The scale factor applies to the exponentals by its square, the stored exponentals
+        // are the scaled ones and are written back with a factor of 1.00
+        if cgto_declaration.2 != 1.0 {
+            let exponent_scale = cgto_declaration.2 * cgto_declaration.2;
+            for row in basis_set_data.iter_mut() {
+                row[0] *= exponent_scale;
+            }
+        }
+
+        if let Some(tolerance) = reader.config.duplicate_exponent_tolerance {
+            let exponentals: Vec<f64> = basis_set_data.iter().map(|row| row[0]).collect();
+            if has_duplicate_values(&exponentals, tolerance) {
+                return Err(BasisSetParseError::DuplicateExponents {
+                    line: declaration_line_number,
+                    text: declaration_line.clone(),
+                });
+            }
         }
 
-        add_basis_set_cgto(&mut basis_set, &cgto_declaration.0, &basis_set_data);
+        add_basis_set_cgto(&mut basis_set, &cgto_declaration.0, &basis_set_data).map_err(
+            |err| match err {
+                BasisSetValidationError::UnsupportedAngularMomentum => {
+                    BasisSetParseError::UnknownAngularMomentum {
+                        line: declaration_line_number,
+                        text: declaration_line.clone(),
+                    }
+                }
+                _ => BasisSetParseError::BadColumnCount {
+                    line: declaration_line_number,
+                    text: declaration_line.clone(),
+                },
+            },
+        )?;
 
-        read_result = read_single_basis_set_line(stream)?;
+        read_result = reader.read_single_basis_set_line()?;
+    }
+    if read_result == BasisSetLine::Eof && reader.config.require_terminator {
+        return Err(BasisSetParseError::UnexpectedEof);
     }
 
     Ok((basis_set_assignment_type, basis_set))
 }
 
-impl ToString for BasisSetAssignmentType {
-    fn to_string(&self) -> String {
-        todo!()
+pub fn read_basis_set(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<(BasisSetAssignmentType, AtomicBasisSet), Box<dyn Error>> {
+    read_basis_set_from(stream)
+}
+
+/// Same as `read_basis_set`, with every contraction normalized as integral codes expect, see
+/// `AtomicBasisSet::normalize`
+pub fn read_basis_set_normalized(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<(BasisSetAssignmentType, AtomicBasisSet), Box<dyn Error>> {
+    let (basis_set_assignment_type, mut basis_set) = read_basis_set_from(stream)?;
+    basis_set.normalize();
+    Ok((basis_set_assignment_type, basis_set))
+}
+
+/// Same as `read_basis_set`, but generic over the line iterator, e.g. `BufRead::lines()`, so
+/// the reading loop is statically dispatched
+pub fn read_basis_set_from<I>(
+    stream: &mut I,
+) -> Result<(BasisSetAssignmentType, AtomicBasisSet), Box<dyn Error>>
+where
+    I: Iterator<Item = Result<String, std::io::Error>> + ?Sized,
+{
+    read_basis_set_with_config(stream, &ReaderConfig::default())
+}
+
+pub fn read_basis_set_with_config<I>(
+    stream: &mut I,
+    config: &ReaderConfig,
+) -> Result<(BasisSetAssignmentType, AtomicBasisSet), Box<dyn Error>>
+where
+    I: Iterator<Item = Result<String, std::io::Error>> + ?Sized,
+{
+    let mut reader = BasisSetLineReader::new(stream, config);
+    let first_line = reader.read_single_basis_set_line()?.into_line();
+    Ok(read_basis_set_block(&first_line, &mut reader)?)
+}
+
+/// Reads a basis set together with the comment lines before the atom declaration, e.g. the
+/// name and version of the basis set in files from the Basis Set Exchange. These comments
+/// describe the whole file, hence they are returned apart from the basis set. The leading `!`
+/// and surrounding whitespaces are stripped.
+pub fn read_basis_set_with_metadata(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<(Vec<String>, BasisSetAssignmentType, AtomicBasisSet), Box<dyn Error>> {
+    let config = ReaderConfig::default();
+    let mut reader = BasisSetLineReader::new(stream, &config);
+    reader.comments = Some(vec![]);
+    let first_line = reader.read_single_basis_set_line()?.into_line();
+    let comments = reader.comments.take().unwrap_or_default();
+    let (basis_set_assignment_type, basis_set) = read_basis_set_block(&first_line, &mut reader)?;
+    Ok((comments, basis_set_assignment_type, basis_set))
+}
+
+/// Reads all the atom blocks in a basis set file, until the stream is exhausted
+pub fn read_all_basis_sets(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let config = ReaderConfig::default();
+    let mut reader = BasisSetLineReader::new(stream, &config);
+    let mut basis_sets = vec![];
+    // Only blank lines and comments are left after the last block
+    let mut first_line = reader.read_single_basis_set_line()?.into_line();
+    while first_line.is_some() {
+        basis_sets.push(read_basis_set_block(&first_line, &mut reader)?);
+        first_line = reader.read_single_basis_set_line()?.into_line();
+    }
+    Ok(basis_sets)
+}
+
+/// Reads the basis sets of a `gen` job from a Gaussian input file (`.gjf`/`.com`). Link 0
+/// commands are followed by the route section, starting with `#`, the title and the molecule
+/// specification, each ended by a blank line. The basis section comes right after the molecule
+/// and ends with the next blank line, anything past it such as ECPs is not read.
+pub fn read_basis_from_input(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let mut in_route = false;
+    // Blank lines ending the route, the title and the molecule specification
+    let mut num_sections_left = 3;
+    while num_sections_left > 0 {
+        let line = stream
+            .next()
+            .ok_or(BasisSetParseError::UnexpectedEof)?
+            .map_err(BasisSetParseError::Io)?;
+        let trimmed = line.trim();
+        if !in_route {
+            // Link 0 commands start with %, they and blank lines may precede the route
+            in_route = trimmed.starts_with('#');
+        } else if trimmed.is_empty() {
+            num_sections_left -= 1;
+        }
+    }
+
+    let mut basis_section =
+        stream.take_while(|item| item.as_ref().map_or(true, |line| !line.trim().is_empty()));
+    read_all_basis_sets(&mut basis_section)
+}
+
+/// Reads the atom blocks whose parsed assignment, formatted by its `Display`, is in `wanted`,
+/// e.g. `O`. Ghost atoms match in their `C-Bq` form however the file spells them, e.g. `Bq-C`
+/// or `C-Gh`. The other blocks are skipped up to their `****` without being parsed.
+pub fn read_basis_sets_for(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+    wanted: &HashSet<String>,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let config = ReaderConfig::default();
+    let mut reader = BasisSetLineReader::new(stream, &config);
+    let mut basis_sets = vec![];
+    let mut first_line = reader.read_single_basis_set_line()?.into_line();
+    while first_line.is_some() {
+        let assignment = parse_basis_set_first_line(&first_line, reader.line_number)?;
+        if wanted.contains(&assignment.to_string()) {
+            basis_sets.push(read_basis_set_block(&first_line, &mut reader)?);
+        } else {
+            reader.skip_basis_set_block()?;
+        }
+        first_line = reader.read_single_basis_set_line()?.into_line();
+    }
+    Ok(basis_sets)
+}
+
+/// Reads all the atom blocks of a basis set file
+pub fn read_basis_set_from_path<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let file = File::open(path)?;
+    read_all_basis_sets(&mut BufReader::new(file).lines())
+}
+
+/// Reads all the atom blocks of a gzip-compressed basis set file, decompressing while reading
+#[cfg(feature = "flate2")]
+pub fn read_basis_set_from_gz<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let file = File::open(path)?;
+    read_all_basis_sets(&mut BufReader::new(flate2::read::GzDecoder::new(file)).lines())
+}
+
+/// Same as `read_basis_set`, but awaiting the lines of an async reader. The lines of the block
+/// are read up to its terminator before being parsed by the synchronous reader, so the reader
+/// is left at the start of the next block.
+#[cfg(feature = "tokio")]
+pub async fn read_basis_set_async<R>(
+    reader: &mut R,
+) -> Result<(BasisSetAssignmentType, AtomicBasisSet), Box<dyn Error>>
+where
+    R: tokio::io::AsyncBufRead + Unpin,
+{
+    use tokio::io::AsyncBufReadExt;
+
+    let mut lines = vec![];
+    let mut async_lines = reader.lines();
+    while let Some(line) = async_lines.next_line().await? {
+        let terminated = is_terminator(&line);
+        lines.push(Ok(line));
+        if terminated {
+            break;
+        }
+    }
+    read_basis_set_from(&mut lines.into_iter())
+}
+
+fn parse_ecp_first_line(
+    line: &Option<String>,
+    line_number: usize,
+) -> Result<(AngularMomentum, usize), BasisSetParseError> {
+    match line {
+        None => Err(BasisSetParseError::UnexpectedEof),
+        Some(declaration_line) => {
+            let bad_ecp = || BasisSetParseError::BadEcp {
+                line: line_number,
+                text: declaration_line.clone(),
+            };
+            // NAME-ECP  max angular momentum  number of core electrons
+            let split: Vec<&str> = declaration_line.split_whitespace().collect();
+            if split.len() < 3 {
+                return Err(bad_ecp());
+            }
+            let max_angular_momentum = split[1]
+                .parse::<usize>()
+                .ok()
+                .and_then(|l| AngularMomentum::try_from(l).ok())
+                .ok_or_else(bad_ecp)?;
+            let num_core_electrons = split[2].parse().map_err(|_| bad_ecp())?;
+            Ok((max_angular_momentum, num_core_electrons))
+        }
+    }
+}
+
+fn read_ecp_terms<I>(reader: &mut BasisSetLineReader<I>) -> Result<Vec<EcpTerm>, BasisSetParseError>
+where
+    I: Iterator<Item = Result<String, std::io::Error>> + ?Sized,
+{
+    // The title line, e.g. "d-ul potential", carries no information
+    reader
+        .read_single_basis_set_line()?
+        .into_line()
+        .ok_or(BasisSetParseError::UnexpectedEof)?;
+    let count_line = reader
+        .read_single_basis_set_line()?
+        .into_line()
+        .ok_or(BasisSetParseError::UnexpectedEof)?;
+    let num_terms: usize = count_line
+        .trim()
+        .parse()
+        .map_err(|_| BasisSetParseError::BadEcp {
+            line: reader.line_number,
+            text: count_line.clone(),
+        })?;
+
+    let mut terms = vec![];
+    for _ in 0..num_terms {
+        // power, exponental, coefficient
+        let term_line = reader.read_single_basis_set_line()?.into_line();
+        let row = parse_floats(&term_line, reader.line_number)?;
+        let bad_ecp = || BasisSetParseError::BadEcp {
+            line: reader.line_number,
+            text: term_line.clone().unwrap_or_default(),
+        };
+        if row.len() != 3 {
+            return Err(bad_ecp());
+        }
+        // The power of r is an integer, e.g. 2.7 is a corrupt file rather than 2
+        let power = term_line
+            .as_deref()
+            .and_then(|line| line.split_whitespace().next())
+            .and_then(|value| value.parse::<i32>().ok())
+            .ok_or_else(bad_ecp)?;
+        terms.push(EcpTerm::new(power, row[1], row[2]));
+    }
+    Ok(terms)
+}
+
+/// Reads an effective core potential block, e.g.
+///
+/// ```text
+/// CU     0
+/// CU-ECP     2     10
+/// d-ul potential
+///   1
+/// 1    511.9951763           -10.0000000
+/// s-ul potential
+/// ...
+/// ```
+///
+/// The first potential is the local part, followed by one projected potential for each
+/// angular momentum below the maximum one.
+pub fn read_ecp(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<(BasisSetAssignmentType, EffectiveCorePotential), Box<dyn Error>> {
+    let config = ReaderConfig::default();
+    let mut reader = BasisSetLineReader::new(stream, &config);
+    let first_line = reader.read_single_basis_set_line()?.into_line();
+    let basis_set_assignment_type = parse_basis_set_first_line(&first_line, reader.line_number)?;
+    let ecp_line = reader.read_single_basis_set_line()?.into_line();
+    let (max_angular_momentum, num_core_electrons) =
+        parse_ecp_first_line(&ecp_line, reader.line_number)?;
+
+    let mut ecp = EffectiveCorePotential::new(num_core_electrons, max_angular_momentum);
+    for term in read_ecp_terms(&mut reader)? {
+        ecp.add_local_term(term);
+    }
+    for angular_momentum in AngularMomentum::all()
+        .iter()
+        .take(max_angular_momentum as usize)
+    {
+        for term in read_ecp_terms(&mut reader)? {
+            ecp.add_projected_term(*angular_momentum, term);
+        }
+    }
+
+    Ok((basis_set_assignment_type, ecp))
+}
+
+/// Writes the basis set as a Gaussian94 block that `read_basis_set` reads back, the shells
+/// grouped by angular momentum and closed by `****`. The basis set does not know its atom, so
+/// the declaration line names the dummy atom `X` next to the declaration flag, e.g. `X     0`;
+/// `GaussianWriter` writes the real assignment. Shells stored beyond the highest supported
+/// angular momentum have no letter and fail the formatting.
+impl std::fmt::Display for AtomicBasisSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "X     {}", self.get_declaration_flag())?;
+        GaussianWriter::default().write_shells(self, f)
+    }
+}
+
+/// Formats the value with the given number of significant figures, `None` for the shortest
+/// representation that reads back exactly
+fn format_float(value: f64, precision: Option<usize>, scientific: bool) -> String {
+    match (precision, scientific) {
+        (None, false) => value.to_string(),
+        (Some(precision), false) => {
+            let magnitude = if value == 0.0 {
+                0
+            } else {
+                value.abs().log10().floor() as i32
+            };
+            let decimals = (precision.max(1) as i32 - 1 - magnitude).max(0) as usize;
+            format!("{:.*}", decimals, value)
+        }
+        (_, true) => {
+            let formatted = match precision {
+                None => format!("{:E}", value),
+                Some(precision) => format!("{:.*E}", precision.max(1) - 1, value),
+            };
+            // Signed two digit exponent as in Fortran output, e.g. 4.5632400000E+03
+            let (mantissa, exponent) = formatted.split_once('E').unwrap();
+            let exponent: i32 = exponent.parse().unwrap();
+            let sign = if exponent < 0 { '-' } else { '+' };
+            format!("{}E{}{:02}", mantissa, sign, exponent.abs())
+        }
+    }
+}
+
+/// Writes atom blocks in the Gaussian94 format, the declaration line followed by the shells.
+/// The default writes every value with the shortest representation that reads back exactly,
+/// as `Display` does.
+#[derive(Clone, Default)]
+pub struct GaussianWriter {
+    /// Significant figures of the exponentals, `None` for the shortest exact representation
+    pub exponent_precision: Option<usize>,
+    /// Significant figures of the coefficients, `None` for the shortest exact representation
+    pub coefficient_precision: Option<usize>,
+    /// Writes values in scientific notation, e.g. `4.5632400000E+03`
+    pub scientific: bool,
+}
+
+impl GaussianWriter {
+    fn write_shells(
+        &self,
+        basis_set: &AtomicBasisSet,
+        f: &mut dyn std::fmt::Write,
+    ) -> std::fmt::Result {
+        // Contractions read from one SP or general contraction shell are written together again
+        for (angular_momentums, segmented_contractions) in basis_set.combined_shells() {
+            // Shells without a letter cannot be written
+            if angular_momentums.contains(&AngularMomentum::UnsupportedAngularMomentum) {
+                return Err(std::fmt::Error);
+            }
+            let letters: String = if angular_momentums
+                .iter()
+                .all(|angular_momentum| *angular_momentum == angular_momentums[0])
+            {
+                angular_momentums[0].symbol().to_string()
+            } else {
+                angular_momentums
+                    .iter()
+                    .map(|angular_momentum| angular_momentum.symbol())
+                    .collect()
+            };
+            let num_primitives = segmented_contractions[0].get_num_primitives();
+            writeln!(f, "{}    {}   1.00", letters, num_primitives)?;
+            for index in 0..num_primitives {
+                write!(
+                    f,
+                    "{:>20}",
+                    format_float(
+                        segmented_contractions[0].get(index).unwrap().exponental(),
+                        self.exponent_precision,
+                        self.scientific
+                    )
+                )?;
+                for segmented_contraction in &segmented_contractions {
+                    write!(
+                        f,
+                        " {:>20}",
+                        format_float(
+                            segmented_contraction.get(index).unwrap().coefficient(),
+                            self.coefficient_precision,
+                            self.scientific
+                        )
+                    )?;
+                }
+                writeln!(f)?;
+            }
+        }
+        writeln!(f, "****")
+    }
+}
+
+impl BasisSetWriter for GaussianWriter {
+    fn write_basis_set(
+        &self,
+        assignment: &BasisSetAssignmentType,
+        basis_set: &AtomicBasisSet,
+        writer: &mut dyn Write,
+    ) -> std::io::Result<()> {
+        check_angular_momentums(basis_set)?;
+        let mut shells = String::new();
+        self.write_shells(basis_set, &mut shells)
+            .expect("checked shells format into a String");
+        writeln!(
+            writer,
+            "{}     {}",
+            assignment,
+            basis_set.get_declaration_flag()
+        )?;
+        write!(writer, "{}", shells)
     }
 }
 
-impl ToString for AtomicBasisSet {
-    fn to_string(&self) -> String {
-        todo!()
+/// Writes the atom blocks into one Gaussian94 file, the inverse of `read_all_basis_sets`. Every
+/// block ends with its own `****`.
+pub fn write_all_basis_sets(
+    entries: &[(BasisSetAssignmentType, AtomicBasisSet)],
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    for (assignment, basis_set) in entries {
+        GaussianWriter::default().write_basis_set(assignment, basis_set, writer)?;
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::io::{BufRead, Cursor};
+    use std::{
+        collections::HashSet,
+        io::{BufRead, Cursor},
+    };
 
     use approx::assert_abs_diff_eq;
 
     use crate::{
-        details::angular_momentum::AngularMomentum,
-        io::gaussian::{parse_basis_set_first_line, BasisSetAssignmentType},
+        details::{
+            angular_momentum::AngularMomentum,
+            atomic_basis_set::{tests::sample_basis_set, AtomicBasisSet},
+            gaussian_exp::overlap_same_center,
+            library::BasisSetLibrary,
+        },
+        io::gaussian::{parse_basis_set_first_line, BasisSetAssignmentType, BasisSetParseError},
+        io::BasisSetWriter,
     };
 
-    use super::{parse_cgto_first_line, parse_floats, read_basis_set};
+    use super::{
+        parse_cgto_first_line, parse_floats, read_all_basis_sets, read_basis_from_input,
+        read_basis_set, read_basis_set_from, read_basis_set_from_path, read_basis_set_normalized,
+        read_basis_set_with_config, read_basis_set_with_metadata, read_basis_sets_for, read_ecp,
+        write_all_basis_sets, GaussianWriter, ReaderConfig,
+    };
 
     #[test]
     fn test_parse_floats() {
-        assert!(parse_floats(&None).is_err());
-        assert!(parse_floats(&Some("1.0 err 2.0".to_string())).is_err());
+        assert!(parse_floats(&None, 0).is_err());
+        assert!(parse_floats(&Some("1.0 err 2.0".to_string()), 0).is_err());
 
-        let parsed = parse_floats(&Some("1.0 2.0 3.0".to_string())).unwrap();
+        let parsed = parse_floats(&Some("1.0 2.0 3.0".to_string()), 0).unwrap();
         assert_eq!(parsed.len(), 3);
         assert_abs_diff_eq!(parsed[0], 1.0);
         assert_abs_diff_eq!(parsed[1], 2.0);
         assert_abs_diff_eq!(parsed[2], 3.0);
+
+        let parsed = parse_floats(&Some("1.0D+00 2.5d-01 4.563240D+03".to_string()), 0).unwrap();
+        assert_eq!(parsed, vec![1.0, 0.25, 4563.24]);
+        assert!(parse_floats(&Some("D+00".to_string()), 0).is_err());
+        assert!(parse_floats(&Some("1.0D".to_string()), 0).is_err());
+        assert!(parse_floats(&Some("1.0D+0D1".to_string()), 0).is_err());
+        assert!(parse_floats(&Some("1.0E+00D1".to_string()), 0).is_err());
     }
 
     #[test]
     fn test_parse_cgto_first_line() {
-        assert!(parse_cgto_first_line(&None).is_err());
-        assert!(parse_cgto_first_line(&Some(" S ".to_string())).is_err());
+        assert!(parse_cgto_first_line(&None, 0).is_err());
+        assert!(parse_cgto_first_line(&Some(" S ".to_string()), 0).is_err());
 
-        let good = parse_cgto_first_line(&Some(" SP 6 ".to_string())).unwrap();
+        let good = parse_cgto_first_line(&Some(" SP 6 ".to_string()), 0).unwrap();
         assert!(good.0 == "SP");
         assert_eq!(good.1, 6);
+        assert_eq!(good.2, 1.0);
+
+        let scaled = parse_cgto_first_line(&Some("S 3 1.24".to_string()), 0).unwrap();
+        assert_eq!(scaled.2, 1.24);
+        assert!(parse_cgto_first_line(&Some("S 3 1.0x".to_string()), 0).is_err());
+        assert!(parse_cgto_first_line(&Some("S 3 0.0".to_string()), 0).is_err());
+        assert!(matches!(
+            parse_cgto_first_line(&Some("S -3 1.00".to_string()), 7),
+            Err(BasisSetParseError::BadCgtoHeader { line: 7, .. })
+        ));
     }
 
     #[test]
     fn test_parse_basis_set_first_line() {
-        assert!(parse_basis_set_first_line(&None).is_err());
+        assert!(parse_basis_set_first_line(&None, 0).is_err());
 
         assert_eq!(
-            parse_basis_set_first_line(&Some("C 0".to_string())).unwrap(),
+            parse_basis_set_first_line(&Some("C 0".to_string()), 0).unwrap(),
             BasisSetAssignmentType::Atom("C".to_string())
         );
 
         assert_eq!(
-            parse_basis_set_first_line(&Some("1 0".to_string())).unwrap(),
+            parse_basis_set_first_line(&Some("1 0".to_string()), 0).unwrap(),
             BasisSetAssignmentType::ParticleIndex(1)
         );
+
+        assert_eq!(
+            parse_basis_set_first_line(&Some("0.0 0.0 1.5".to_string()), 0).unwrap(),
+            BasisSetAssignmentType::Center {
+                x: 0.0,
+                y: 0.0,
+                z: 1.5
+            }
+        );
+        assert_eq!(
+            parse_basis_set_first_line(&Some(" -1.2 0.5D+00 3.0 0".to_string()), 0).unwrap(),
+            BasisSetAssignmentType::Center {
+                x: -1.2,
+                y: 0.5,
+                z: 3.0
+            }
+        );
+        // Integral coordinates are fine as long as one of them is not
+        assert_eq!(
+            parse_basis_set_first_line(&Some("0 0 1.5".to_string()), 0).unwrap(),
+            BasisSetAssignmentType::Center {
+                x: 0.0,
+                y: 0.0,
+                z: 1.5
+            }
+        );
+        // Fewer than three coordinates are not a center
+        assert!(matches!(
+            parse_basis_set_first_line(&Some("1.5 0".to_string()), 7),
+            Err(BasisSetParseError::BadDeclaration { line: 7, .. })
+        ));
+
+        for (line, expected) in [
+            ("Bq 0", ""),
+            ("C-gh 0", "C"),
+            ("Ghost-C 0", "C"),
+            ("o-BQ", "o"),
+        ] {
+            assert_eq!(
+                parse_basis_set_first_line(&Some(line.to_string()), 0).unwrap(),
+                BasisSetAssignmentType::Ghost(expected.to_string())
+            );
+        }
+        // A dash alone does not make a ghost
+        assert_eq!(
+            parse_basis_set_first_line(&Some("C-H 0".to_string()), 0).unwrap(),
+            BasisSetAssignmentType::Atom("C-H".to_string())
+        );
+        let ghost = BasisSetAssignmentType::Ghost("C".to_string());
+        assert_eq!(
+            parse_basis_set_first_line(&Some(ghost.to_string()), 0).unwrap(),
+            ghost
+        );
+    }
+
+    #[test]
+    fn test_basis_set_assignment_type_to_string() {
+        let atom = BasisSetAssignmentType::Atom("C".to_string());
+        assert_eq!(atom.to_string(), "C");
+        assert_eq!(
+            parse_basis_set_first_line(&Some(atom.to_string()), 0).unwrap(),
+            atom
+        );
+
+        let particle_index = BasisSetAssignmentType::ParticleIndex(1);
+        assert_eq!(particle_index.to_string(), "1");
+        assert_eq!(
+            parse_basis_set_first_line(&Some(particle_index.to_string()), 0).unwrap(),
+            particle_index
+        );
+
+        let center = BasisSetAssignmentType::Center {
+            x: 0.0,
+            y: -2.0,
+            z: 1.5,
+        };
+        assert_eq!(
+            parse_basis_set_first_line(&Some(format!("{} 0", center)), 0).unwrap(),
+            center
+        );
     }
 
     // 6-311G basis set for C
-    const CARBON_BASIS_SET: &'static str = "\n
+    const CARBON_BASIS_SET: &str = "\n
 !----------------------------------------------------------------------
 ! Basis Set Exchange
 ! Version v0.9
@@ -258,6 +1074,19 @@ SP   1   1.00
 SP   1   1.00
       0.1455850              1.000000               1.000000
 ****
+";
+
+    // STO-3G basis set for O
+    const OXYGEN_BASIS_SET: &str = "O     0
+S    3   1.00
+    130.7093200              0.15432897
+     23.8088610              0.53532814
+      6.4436083              0.44463454
+SP   3   1.00
+      5.0331513             -0.09996723             0.15591627
+      1.1695961              0.39951283             0.60768372
+      0.3803890              0.70011547             0.39195739
+****
 ";
 
     #[test]
@@ -277,25 +1106,1203 @@ SP   1   1.00
         let (cgto1_am, cgto1_sc) = cgto_iter.next().unwrap();
         assert_eq!(cgto1_am, AngularMomentum::S);
         assert_eq!(cgto1_sc.get_num_primitives(), 6);
-        assert_abs_diff_eq!(cgto1_sc.get(2).unwrap().coefficient(), 154.9730);
-        assert_abs_diff_eq!(cgto1_sc.get(3).unwrap().exponental(), 0.2608010);
+        assert_abs_diff_eq!(cgto1_sc.get(2).unwrap().exponental(), 154.9730);
+        assert_abs_diff_eq!(cgto1_sc.get(3).unwrap().coefficient(), 0.2608010);
 
         cgto_iter.next();
         let (cgto2_am, cgto2_sc) = cgto_iter.next().unwrap();
         assert_eq!(cgto2_am, AngularMomentum::S);
         assert_eq!(cgto2_sc.get_num_primitives(), 1);
-        assert_abs_diff_eq!(cgto2_sc.get(0).unwrap().coefficient(), 0.4834560);
-        assert_abs_diff_eq!(cgto2_sc.get(0).unwrap().exponental(), 1.0);
+        assert_abs_diff_eq!(cgto2_sc.get(0).unwrap().exponental(), 0.4834560);
+        assert_abs_diff_eq!(cgto2_sc.get(0).unwrap().coefficient(), 1.0);
 
         cgto_iter.next();
         let (cgto3_am, cgto3_sc) = cgto_iter.next().unwrap();
         assert_eq!(cgto3_am, AngularMomentum::P);
         assert_eq!(cgto3_sc.get_num_primitives(), 3);
-        assert_abs_diff_eq!(cgto3_sc.get(2).unwrap().coefficient(), 1.459330);
-        assert_abs_diff_eq!(cgto3_sc.get(2).unwrap().exponental(), 0.815854);
+        assert_abs_diff_eq!(cgto3_sc.get(2).unwrap().exponental(), 1.459330);
+        assert_abs_diff_eq!(cgto3_sc.get(2).unwrap().coefficient(), 0.815854);
 
         cgto_iter.next();
         cgto_iter.next();
         assert!(cgto_iter.next().is_none());
     }
+
+    #[test]
+    fn test_num_functions_carbon_basis_set() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        // 4 S and 3 P shells
+        assert_eq!(basis_set.get_num_spherical_functions(), 13);
+        assert_eq!(basis_set.get_num_cartesian_functions(), 13);
+    }
+
+    #[test]
+    fn test_get_contraction() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        let (angular_momentum, first) = basis_set.get_contraction(0).unwrap();
+        assert_eq!(angular_momentum, AngularMomentum::S);
+        assert_eq!(first.get_num_primitives(), 6);
+
+        let last_index = basis_set.get_num_contracted_functions() - 1;
+        let (angular_momentum, last) = basis_set.get_contraction(last_index).unwrap();
+        assert_eq!(angular_momentum, AngularMomentum::P);
+        assert_eq!(last.get(0).unwrap().exponental(), 0.1455850);
+        assert!(basis_set.get_contraction(last_index + 1).is_none());
+
+        for (index, (angular_momentum, contraction)) in basis_set.into_iter().enumerate() {
+            assert_eq!(
+                basis_set.get_contraction(index),
+                Some((angular_momentum, contraction))
+            );
+        }
+    }
+
+    #[test]
+    fn test_remove_diffuse_carbon_basis_set() {
+        let (_, mut basis_set) =
+            read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        // Only the outermost SP shell lies below the threshold
+        basis_set.remove_diffuse(0.2);
+        assert_eq!(basis_set.get_num_contracted_functions(), 5);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 14);
+        assert_eq!(basis_set.unique_exponents(AngularMomentum::P).len(), 4);
+
+        // Partially cut contractions keep their tighter primitives
+        basis_set.remove_diffuse(2.0);
+        let num_primitives: Vec<(AngularMomentum, usize)> = basis_set
+            .into_iter()
+            .map(|(am, contraction)| (am, contraction.get_num_primitives()))
+            .collect();
+        assert_eq!(
+            num_primitives,
+            vec![
+                (AngularMomentum::S, 5),
+                (AngularMomentum::S, 2),
+                (AngularMomentum::P, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_basis_set_normalized() {
+        let (assignment, basis_set) =
+            read_basis_set_normalized(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        assert_eq!(assignment, BasisSetAssignmentType::Atom("C".to_string()));
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 16);
+        for (angular_momentum, contraction) in &basis_set {
+            assert_abs_diff_eq!(
+                overlap_same_center(contraction, contraction, angular_momentum),
+                1.0,
+                epsilon = 1e-12
+            );
+        }
+
+        // The S contraction holds normalized coefficients rather than the file's
+        let (_, raw_basis_set) =
+            read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        let (_, s_contraction) = basis_set.into_iter().next().unwrap();
+        let (_, raw_s_contraction) = raw_basis_set.into_iter().next().unwrap();
+        assert!(!s_contraction.approx_eq(raw_s_contraction, 1e-8));
+        assert!(s_contraction.shares_exponents(raw_s_contraction));
+    }
+
+    #[test]
+    fn test_read_basis_set_from() {
+        let mut lines = Cursor::new(OXYGEN_BASIS_SET).lines();
+        let (assignment_type, basis_set) = read_basis_set_from(&mut lines).unwrap();
+        assert_eq!(
+            assignment_type,
+            BasisSetAssignmentType::Atom("O".to_string())
+        );
+        assert_eq!(basis_set.get_num_contracted_functions(), 3);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 9);
+    }
+
+    #[test]
+    fn test_clone_carbon_basis_set() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        let mut scaled = basis_set.clone();
+        scaled.scale_exponents(2.0);
+
+        let (_, original) = basis_set.into_iter().next().unwrap();
+        let (_, scaled) = scaled.into_iter().next().unwrap();
+        assert_abs_diff_eq!(original.get(0).unwrap().exponental(), 4563.24);
+        assert_abs_diff_eq!(scaled.get(0).unwrap().exponental(), 9126.48);
+    }
+
+    #[test]
+    fn test_eq_carbon_basis_set() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        let (_, same_basis_set) =
+            read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_set, same_basis_set);
+
+        let (_, oxygen_basis_set) =
+            read_basis_set(&mut Cursor::new(OXYGEN_BASIS_SET).lines()).unwrap();
+        assert_ne!(basis_set, oxygen_basis_set);
+    }
+
+    #[test]
+    fn test_primitives_per_angular_momentum() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        // 6 + 3 + 1 + 1 S primitives, the SP shells give 3 + 1 + 1 P primitives
+        assert_eq!(
+            basis_set.primitives_per_angular_momentum(),
+            vec![(AngularMomentum::S, 11), (AngularMomentum::P, 5)]
+        );
+    }
+
+    #[test]
+    fn test_gaussian_writer() {
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_shell(
+                AngularMomentum::S,
+                &[0.15432897, 0.53532814, 0.44463454],
+                &[3.42525091, 0.62391373, 0.1688554],
+            )
+            .unwrap();
+
+        let writer: Box<dyn BasisSetWriter> = Box::new(GaussianWriter::default());
+        let mut output = Vec::<u8>::new();
+        writer
+            .write_basis_set(
+                &BasisSetAssignmentType::Atom("H".to_string()),
+                &basis_set,
+                &mut output,
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "H     0
+S    3   1.00
+          3.42525091           0.15432897
+          0.62391373           0.53532814
+           0.1688554           0.44463454
+****
+"
+        );
+    }
+
+    #[test]
+    fn test_gaussian_writer_precision() {
+        let (assignment, basis_set) =
+            read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+
+        let writer = GaussianWriter {
+            exponent_precision: Some(10),
+            coefficient_precision: Some(10),
+            ..Default::default()
+        };
+        let mut output = Vec::<u8>::new();
+        writer
+            .write_basis_set(&assignment, &basis_set, &mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("\n         4563.240000       0.001966650000\n"));
+        assert!(output.contains("        0.1455850000          1.000000000          1.000000000\n"));
+
+        let (_, round_trip) = read_basis_set(&mut Cursor::new(output).lines()).unwrap();
+        for ((_, contraction), (_, round_trip_contraction)) in
+            basis_set.into_iter().zip(&round_trip)
+        {
+            assert!(contraction.approx_eq(round_trip_contraction, 1e-10));
+        }
+
+        let writer = GaussianWriter {
+            exponent_precision: Some(11),
+            coefficient_precision: Some(11),
+            scientific: true,
+        };
+        let mut output = Vec::<u8>::new();
+        writer
+            .write_basis_set(&assignment, &basis_set, &mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("    4.5632400000E+03     1.9666500000E-03\n"));
+        assert!(output.contains("    1.4593300000E+00    -3.0306800000E-03     8.1585400000E-01\n"));
+        let (_, round_trip) = read_basis_set(&mut Cursor::new(output).lines()).unwrap();
+        assert_eq!(round_trip, basis_set);
+    }
+
+    #[test]
+    fn test_to_shell_tuples() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        let shells = basis_set.to_shell_tuples();
+        assert_eq!(shells.len(), 7);
+
+        let (angular_momentum, coefficients, exponentals) = &shells[0];
+        assert_eq!(*angular_momentum, AngularMomentum::S);
+        assert_eq!(coefficients.len(), 6);
+        assert_eq!(exponentals.len(), 6);
+        assert_eq!(coefficients[0], 0.00196665);
+        assert_eq!(exponentals[0], 4563.240);
+        assert_eq!(shells[6].0, AngularMomentum::P);
+        assert_eq!(shells[6].2, vec![0.1455850]);
+    }
+
+    #[test]
+    fn test_extract_angular_momentum() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        let p_basis_set = basis_set.extract_angular_momentum(AngularMomentum::P);
+        assert_eq!(p_basis_set.get_num_contracted_functions(), 3);
+        assert_eq!(p_basis_set.get_num_gaussian_primitives(), 5);
+        assert_eq!(
+            p_basis_set.primitives_per_angular_momentum(),
+            vec![(AngularMomentum::P, 5)]
+        );
+        assert_eq!(
+            p_basis_set
+                .into_iter()
+                .filter(|(angular_momentum, _)| *angular_momentum == AngularMomentum::S)
+                .count(),
+            0
+        );
+        assert_eq!(
+            p_basis_set.unique_exponents(AngularMomentum::P),
+            basis_set.unique_exponents(AngularMomentum::P)
+        );
+
+        let d_basis_set = basis_set.extract_angular_momentum(AngularMomentum::D);
+        assert_eq!(d_basis_set.get_num_contracted_functions(), 0);
+    }
+
+    #[test]
+    fn test_contraction_length_histogram() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        let histogram = basis_set.contraction_length_histogram();
+        assert_eq!(histogram.get(&6), Some(&1));
+        assert_eq!(histogram.get(&3), Some(&2));
+        assert_eq!(histogram.get(&1), Some(&4));
+        assert_eq!(histogram.len(), 3);
+        assert!(AtomicBasisSet::new()
+            .contraction_length_histogram()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_contractions_in_exponent_range() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+
+        // Only the first S contraction holds exponentals above 100
+        let tight = basis_set.contractions_in_exponent_range(100.0, 5000.0);
+        assert_eq!(tight.len(), 1);
+        assert_eq!(tight[0].0, AngularMomentum::S);
+        assert_eq!(tight[0].1.get_num_primitives(), 6);
+
+        // The first SP shell, both as S and P, along with the first S contraction
+        let valence = basis_set.contractions_in_exponent_range(1.5, 5.0);
+        assert_eq!(
+            valence.iter().map(|(am, _)| *am).collect::<Vec<_>>(),
+            vec![AngularMomentum::S, AngularMomentum::S, AngularMomentum::P]
+        );
+        assert!(basis_set
+            .contractions_in_exponent_range(5000.0, 10000.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_summary() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        let summary = basis_set.summary();
+        assert_eq!(summary.num_shells, 7);
+        assert_eq!(summary.num_primitives, 16);
+        assert_eq!(summary.highest_angular_momentum, AngularMomentum::P);
+        assert_eq!(
+            summary.shells_per_angular_momentum,
+            vec![(AngularMomentum::S, 4), (AngularMomentum::P, 3)]
+        );
+        assert_eq!(
+            summary.to_string(),
+            "S:4 P:3 (7 shells, 16 primitives, max=P)"
+        );
+        assert_eq!(
+            AtomicBasisSet::new().summary().to_string(),
+            "(0 shells, 0 primitives, max=?)"
+        );
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        let (_, copy) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_set.content_hash(), copy.content_hash());
+
+        let mut scaled = copy.clone();
+        scaled.scale_exponents(1.0 + 1e-12);
+        assert_ne!(basis_set.content_hash(), scaled.content_hash());
+
+        // Moving a primitive into the next contraction changes the hash
+        let first = sample_basis_set();
+        let mut second = AtomicBasisSet::new();
+        second
+            .add_shell(AngularMomentum::S, &[0.4], &[3.0])
+            .unwrap()
+            .add_shell(AngularMomentum::S, &[0.6, 1.0], &[0.5, 0.1])
+            .unwrap()
+            .add_shell(AngularMomentum::P, &[1.0], &[0.8])
+            .unwrap();
+        assert_ne!(first.content_hash(), second.content_hash());
+        assert_ne!(AtomicBasisSet::new().content_hash(), first.content_hash());
+    }
+
+    #[test]
+    fn test_max_min_exponent() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_set.max_exponent(), Some(4563.240));
+        assert_eq!(basis_set.min_exponent(), Some(0.1455850));
+        assert_eq!(AtomicBasisSet::new().max_exponent(), None);
+    }
+
+    #[test]
+    fn test_nominal_orbital_capacity() {
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_set.nominal_orbital_capacity(), 13);
+        assert_eq!(
+            basis_set.nominal_orbital_capacity_per_angular_momentum(),
+            vec![(AngularMomentum::S, 4), (AngularMomentum::P, 9)]
+        );
+    }
+
+    #[test]
+    fn test_write_carbon_basis_set() {
+        let input_stream = Cursor::new(CARBON_BASIS_SET);
+        let (_, basis_set) = read_basis_set(&mut input_stream.lines()).unwrap();
+
+        let output = basis_set.to_string();
+        assert!(output.starts_with("X     0\nS    6   1.00\n"));
+        // SP shells are written back as SP rather than separate S and P shells
+        assert_eq!(output.matches("SP    ").count(), 3);
+        assert!(output.contains(
+            "SP    3   1.00\n             20.9642              0.11466            0.0402487\n"
+        ));
+        assert!(output.contains("4563.24"));
+        assert!(output.ends_with("****\n"));
+
+        let (assignment, round_trip) = read_basis_set(&mut Cursor::new(output).lines()).unwrap();
+        assert_eq!(assignment, BasisSetAssignmentType::Atom("X".to_string()));
+        assert_eq!(
+            round_trip.get_num_contracted_functions(),
+            basis_set.get_num_contracted_functions()
+        );
+        assert_eq!(
+            round_trip.get_num_gaussian_primitives(),
+            basis_set.get_num_gaussian_primitives()
+        );
+        for ((am, sc), (round_trip_am, round_trip_sc)) in basis_set.into_iter().zip(&round_trip) {
+            assert_eq!(am, round_trip_am);
+            assert_eq!(sc.get_num_primitives(), round_trip_sc.get_num_primitives());
+            for index in 0..sc.get_num_primitives() {
+                let primitive = sc.get(index).unwrap();
+                let round_trip_primitive = round_trip_sc.get(index).unwrap();
+                assert_eq!(primitive.exponental(), round_trip_primitive.exponental());
+                assert_eq!(primitive.coefficient(), round_trip_primitive.coefficient());
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_all_basis_sets() {
+        let input_stream = Cursor::new(format!(
+            "{}{}\n\n! Trailing comment\n\n",
+            CARBON_BASIS_SET, OXYGEN_BASIS_SET
+        ));
+
+        let basis_sets = read_all_basis_sets(&mut input_stream.lines()).unwrap();
+        assert_eq!(basis_sets.len(), 2);
+        assert_eq!(
+            basis_sets[0].0,
+            BasisSetAssignmentType::Atom("C".to_string())
+        );
+        assert_eq!(basis_sets[0].1.get_num_contracted_functions(), 7);
+        assert_eq!(
+            basis_sets[1].0,
+            BasisSetAssignmentType::Atom("O".to_string())
+        );
+        assert_eq!(basis_sets[1].1.get_num_contracted_functions(), 3);
+        assert_eq!(basis_sets[1].1.get_num_gaussian_primitives(), 9);
+    }
+
+    #[test]
+    fn test_read_basis_from_input() {
+        // The basis section cannot hold blank lines, so the header of the carbon file is left out
+        let carbon_block = &CARBON_BASIS_SET[CARBON_BASIS_SET.find("C     0").unwrap()..];
+        let input = format!(
+            "%chk=methanal.chk\n%mem=1GB\n# hf/gen\n  scf=tight\n\nMethanal\n\n0 1\n\
+             C   0.0  0.0  0.0\nO   0.0  0.0  1.2\n\n{}{}\nC 0\nlanl2dz\n\n",
+            carbon_block, OXYGEN_BASIS_SET
+        );
+        let basis_sets = read_basis_from_input(&mut Cursor::new(&input).lines()).unwrap();
+        assert_eq!(basis_sets.len(), 2);
+        assert_eq!(
+            basis_sets[0].0,
+            BasisSetAssignmentType::Atom("C".to_string())
+        );
+        let (_, expected) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_sets[0].1, expected);
+        assert_eq!(
+            basis_sets[1].0,
+            BasisSetAssignmentType::Atom("O".to_string())
+        );
+
+        // No basis section after the molecule
+        let error =
+            read_basis_from_input(&mut Cursor::new("# hf/sto-3g\n\nWater\n\n0 1\nO\n").lines())
+                .err()
+                .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn test_read_basis_sets_for() {
+        // The nitrogen block is malformed, which goes unnoticed as it is skipped
+        let input = format!(
+            "{}{}N     0\nS    2   1.00\n 1.0 bad\n****\n",
+            CARBON_BASIS_SET, OXYGEN_BASIS_SET
+        );
+        let wanted = HashSet::from(["O".to_string()]);
+        let basis_sets = read_basis_sets_for(&mut Cursor::new(&input).lines(), &wanted).unwrap();
+        assert_eq!(basis_sets.len(), 1);
+        assert_eq!(
+            basis_sets[0].0,
+            BasisSetAssignmentType::Atom("O".to_string())
+        );
+        let (_, expected) = read_basis_set(&mut Cursor::new(OXYGEN_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_sets[0].1, expected);
+
+        let wanted = HashSet::from(["C".to_string(), "N".to_string()]);
+        assert!(read_basis_sets_for(&mut Cursor::new(&input).lines(), &wanted).is_err());
+        assert!(
+            read_basis_sets_for(&mut Cursor::new(&input).lines(), &HashSet::new())
+                .unwrap()
+                .is_empty()
+        );
+
+        let ghost_input = "Bq-C     0\nS    1   1.00\n      0.5000000      1.0000000\n****\n";
+        let wanted = HashSet::from(["C-Bq".to_string()]);
+        let basis_sets =
+            read_basis_sets_for(&mut Cursor::new(ghost_input).lines(), &wanted).unwrap();
+        assert_eq!(basis_sets.len(), 1);
+        assert_eq!(
+            basis_sets[0].0,
+            BasisSetAssignmentType::Ghost("C".to_string())
+        );
+        for spelling in ["Bq-C", "C"] {
+            let wanted = HashSet::from([spelling.to_string()]);
+            assert!(
+                read_basis_sets_for(&mut Cursor::new(ghost_input).lines(), &wanted)
+                    .unwrap()
+                    .is_empty(),
+                "{}",
+                spelling
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_all_basis_sets() {
+        let input = format!("{}\n{}", CARBON_BASIS_SET, OXYGEN_BASIS_SET);
+        let basis_sets = read_all_basis_sets(&mut Cursor::new(input).lines()).unwrap();
+
+        let mut output = Vec::<u8>::new();
+        write_all_basis_sets(&basis_sets, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("****\n").count(), 2);
+        assert!(output.ends_with("****\n"));
+
+        let round_trip = read_all_basis_sets(&mut Cursor::new(output).lines()).unwrap();
+        assert_eq!(round_trip, basis_sets);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_read_basis_set_async() {
+        let mut reader = Cursor::new(format!("{}{}", CARBON_BASIS_SET, OXYGEN_BASIS_SET));
+        let (assignment, basis_set) = super::read_basis_set_async(&mut reader).await.unwrap();
+        let (expected_assignment, expected) =
+            read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        assert_eq!(assignment, expected_assignment);
+        assert_eq!(basis_set, expected);
+
+        // The reader is left at the next block
+        let (assignment, basis_set) = super::read_basis_set_async(&mut reader).await.unwrap();
+        assert_eq!(assignment, BasisSetAssignmentType::Atom("O".to_string()));
+        assert_eq!(basis_set.get_num_contracted_functions(), 3);
+
+        let error = super::read_basis_set_async(&mut reader)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn test_read_basis_set_from_path() {
+        let path = std::env::temp_dir().join(format!(
+            "rx-basis-read-basis-set-from-path-{}.gbs",
+            std::process::id()
+        ));
+        std::fs::write(&path, format!("{}\n{}", CARBON_BASIS_SET, OXYGEN_BASIS_SET)).unwrap();
+        let basis_sets = read_basis_set_from_path(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let basis_sets = basis_sets.unwrap();
+        assert_eq!(basis_sets.len(), 2);
+        assert_eq!(
+            basis_sets[1].0,
+            BasisSetAssignmentType::Atom("O".to_string())
+        );
+        assert!(read_basis_set_from_path(path).is_err());
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn test_read_basis_set_from_gz() {
+        use std::io::Write;
+
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::<u8>::new(), flate2::Compression::default());
+        encoder.write_all(CARBON_BASIS_SET.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "rx-basis-read-basis-set-from-gz-{}.gbs.gz",
+            std::process::id()
+        ));
+        std::fs::write(&path, compressed).unwrap();
+        let basis_sets = super::read_basis_set_from_gz(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let basis_sets = basis_sets.unwrap();
+        assert_eq!(basis_sets.len(), 1);
+        let (_, expected) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_sets[0].1, expected);
+    }
+
+    #[test]
+    fn test_basis_set_library() {
+        let input = format!("{}\n{}", CARBON_BASIS_SET, OXYGEN_BASIS_SET);
+        let library =
+            BasisSetLibrary::from(read_all_basis_sets(&mut Cursor::new(input).lines()).unwrap());
+        assert_eq!(library.len(), 2);
+
+        let carbon = library
+            .get(&BasisSetAssignmentType::Atom("C".to_string()))
+            .unwrap();
+        assert_eq!(carbon.get_num_contracted_functions(), 7);
+        assert!(library
+            .get(&BasisSetAssignmentType::Atom("N".to_string()))
+            .is_none());
+    }
+
+    #[test]
+    fn test_validate_element_symbols() {
+        let config = ReaderConfig {
+            validate_element_symbols: true,
+            ..Default::default()
+        };
+        let input = OXYGEN_BASIS_SET.replacen("O ", "o ", 1);
+        let (assignment_type, _) =
+            read_basis_set_with_config(&mut Cursor::new(&input).lines(), &config).unwrap();
+        assert_eq!(
+            assignment_type,
+            BasisSetAssignmentType::Atom("O".to_string())
+        );
+
+        let input = OXYGEN_BASIS_SET.replacen("O ", "Ox ", 1);
+        let error = read_basis_set_with_config(&mut Cursor::new(&input).lines(), &config)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::BadDeclaration { line: 1, .. })
+        ));
+        // Without validation any atom name is accepted
+        assert!(read_basis_set(&mut Cursor::new(&input).lines()).is_ok());
+    }
+
+    #[test]
+    fn test_read_loose_terminators() {
+        let input = format!(
+            "{}   ****   \n{}*****\n",
+            OXYGEN_BASIS_SET.trim_end_matches("****\n"),
+            OXYGEN_BASIS_SET.trim_end_matches("****\n")
+        );
+        let basis_sets = read_all_basis_sets(&mut Cursor::new(input).lines()).unwrap();
+        assert_eq!(basis_sets.len(), 2);
+        for (_, basis_set) in &basis_sets {
+            assert_eq!(basis_set.get_num_contracted_functions(), 3);
+        }
+    }
+
+    #[test]
+    fn test_read_tab_separated_columns() {
+        // Every run of spaces replaced by a tab, including the indentation of the terminator
+        let input: String = CARBON_BASIS_SET
+            .lines()
+            .filter(|line| !line.starts_with('!'))
+            .map(|line| {
+                format!(
+                    "\t{}\n",
+                    line.split_whitespace().collect::<Vec<_>>().join("\t")
+                )
+            })
+            .collect();
+        assert!(input.contains("\t****\n"));
+        let (assignment, basis_set) = read_basis_set(&mut Cursor::new(&input).lines()).unwrap();
+        assert_eq!(assignment, BasisSetAssignmentType::Atom("C".to_string()));
+        let (_, expected) = read_basis_set(&mut Cursor::new(CARBON_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_set, expected);
+
+        let header = parse_cgto_first_line(&Some("SP\t3\t1.00".to_string()), 0).unwrap();
+        assert_eq!(header, ("SP".to_string(), 3, 1.0));
+        assert_eq!(
+            parse_floats(&Some("\t4563.240\t0.00196665".to_string()), 0).unwrap(),
+            vec![4563.240, 0.00196665]
+        );
+
+        // Both blocks are found when the terminators are indented with tabs
+        let basis_sets =
+            read_all_basis_sets(&mut Cursor::new(format!("{}{}", input, input)).lines()).unwrap();
+        assert_eq!(basis_sets.len(), 2);
+    }
+
+    #[test]
+    fn test_read_all_basis_sets_without_final_terminator() {
+        let input_stream = Cursor::new(format!(
+            "{}{}",
+            OXYGEN_BASIS_SET,
+            OXYGEN_BASIS_SET.trim_end_matches("****\n")
+        ));
+
+        let basis_sets = read_all_basis_sets(&mut input_stream.lines()).unwrap();
+        assert_eq!(basis_sets.len(), 2);
+        assert_eq!(basis_sets[1].1.get_num_gaussian_primitives(), 9);
+    }
+
+    // S block of cc-pVTZ for C, trimmed to six exponents
+    const CARBON_GENERAL_CONTRACTION: &str = "C     0
+S    6   1.00
+   8236.0000000              0.0005310             -0.0001130              0.0000000
+   1235.0000000              0.0041080             -0.0008780              0.0000000
+    280.8000000              0.0210870             -0.0045400              0.0000000
+     79.2700000              0.0818530             -0.0181330              0.0000000
+     25.5900000              0.2348170             -0.0557600              0.0000000
+      8.9970000              0.4344010             -0.1268950              1.0000000
+****
+";
+
+    #[test]
+    fn test_load_general_contraction() {
+        let input_stream = Cursor::new(CARBON_GENERAL_CONTRACTION);
+
+        let (_, basis_set) = read_basis_set(&mut input_stream.lines()).unwrap();
+        assert_eq!(basis_set.get_num_contracted_functions(), 3);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 18);
+
+        let contractions: Vec<_> = basis_set.into_iter().collect();
+        for (angular_momentum, segmented_contraction) in &contractions {
+            assert_eq!(*angular_momentum, AngularMomentum::S);
+            assert_abs_diff_eq!(segmented_contraction.get(2).unwrap().exponental(), 280.8);
+        }
+        assert_abs_diff_eq!(contractions[0].1.get(5).unwrap().coefficient(), 0.4344010);
+        assert_abs_diff_eq!(contractions[1].1.get(5).unwrap().coefficient(), -0.1268950);
+        assert_abs_diff_eq!(contractions[2].1.get(5).unwrap().coefficient(), 1.0);
+    }
+
+    // Small ECP block for Cu
+    const COPPER_ECP: &str = "
+CU     0
+CU-ECP     2     10
+d-ul potential
+  3
+1    511.9951763           -10.0000000
+2     93.2801074           -72.5548282
+2     23.2206669           -12.7450536
+s-ul potential
+  2
+0    173.1180854             3.0000000
+2     38.6884426           -30.5843461
+p-ul potential
+  2
+0    115.6246200             5.0000000
+2     39.0218007            -16.9547014
+";
+
+    #[test]
+    fn test_load_general_contraction_d_shell() {
+        let input = "Cu 0
+D    4   1.00
+     48.9750000              0.0313260              0.0000000
+     13.3600000              0.1599200              0.0000000
+      4.3990000              0.3945700             -0.2314500
+      1.4380000              0.4753000              0.7625600
+****
+";
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(input).lines()).unwrap();
+        assert_eq!(basis_set.get_num_contracted_functions(), 2);
+        assert_eq!(
+            basis_set.primitives_per_angular_momentum(),
+            vec![(AngularMomentum::D, 8)]
+        );
+
+        let contractions: Vec<_> = basis_set.into_iter().collect();
+        for (angular_momentum, contraction) in &contractions {
+            assert_eq!(*angular_momentum, AngularMomentum::D);
+            assert_eq!(
+                contraction.exponents().collect::<Vec<_>>(),
+                vec![48.975, 13.36, 4.399, 1.438]
+            );
+        }
+        assert_eq!(
+            contractions[0].1.coefficients().collect::<Vec<_>>(),
+            vec![0.031326, 0.15992, 0.39457, 0.4753]
+        );
+        assert_eq!(
+            contractions[1].1.coefficients().collect::<Vec<_>>(),
+            vec![0.0, 0.0, -0.23145, 0.76256]
+        );
+        assert_eq!(
+            basis_set.combined_shells()[0].0,
+            vec![AngularMomentum::D, AngularMomentum::D]
+        );
+    }
+
+    #[test]
+    fn test_write_general_contraction() {
+        let (_, basis_set) =
+            read_basis_set(&mut Cursor::new(CARBON_GENERAL_CONTRACTION).lines()).unwrap();
+        let output = basis_set.to_string();
+        assert_eq!(output.matches("S    ").count(), 1);
+
+        let (_, round_trip) = read_basis_set(&mut Cursor::new(output).lines()).unwrap();
+        assert_eq!(round_trip.get_num_contracted_functions(), 3);
+        assert_eq!(round_trip.combined_shells().len(), 1);
+    }
+
+    #[test]
+    fn test_read_ecp() {
+        let input_stream = Cursor::new(COPPER_ECP);
+
+        let (assignment_type, ecp) = read_ecp(&mut input_stream.lines()).unwrap();
+        assert_eq!(
+            assignment_type,
+            BasisSetAssignmentType::Atom("CU".to_string())
+        );
+        assert_eq!(ecp.get_num_core_electrons(), 10);
+        assert_eq!(ecp.get_max_angular_momentum(), AngularMomentum::D);
+
+        assert_eq!(ecp.local_potential().len(), 3);
+        assert_eq!(ecp.local_potential()[0].power(), 1);
+        assert_abs_diff_eq!(ecp.local_potential()[1].exponental(), 93.2801074);
+        assert_abs_diff_eq!(ecp.local_potential()[2].coefficient(), -12.7450536);
+
+        let s_potential = ecp.projected_potential(AngularMomentum::S).unwrap();
+        assert_eq!(s_potential.len(), 2);
+        assert_abs_diff_eq!(s_potential[1].coefficient(), -30.5843461);
+        let p_potential = ecp.projected_potential(AngularMomentum::P).unwrap();
+        assert_eq!(p_potential[0].power(), 0);
+        assert_abs_diff_eq!(p_potential[0].exponental(), 115.6246200);
+        assert!(ecp.projected_potential(AngularMomentum::D).is_none());
+
+        let bad_power = COPPER_ECP.replacen("2     93.2801074", "2.7   93.2801074", 1);
+        let error = read_ecp(&mut Cursor::new(bad_power).lines()).err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::BadEcp { line: 7, .. })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_carbon_basis_set() {
+        use crate::details::atomic_basis_set::AtomicBasisSet;
+
+        let input_stream = Cursor::new(CARBON_BASIS_SET);
+        let (assignment_type, basis_set) = read_basis_set(&mut input_stream.lines()).unwrap();
+
+        let json = serde_json::to_string(&basis_set).unwrap();
+        let deserialized: AtomicBasisSet = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.get_num_contracted_functions(), 7);
+        assert_eq!(deserialized.get_num_gaussian_primitives(), 16);
+        assert_eq!(deserialized.to_string(), basis_set.to_string());
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+
+        let json = serde_json::to_string(&assignment_type).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BasisSetAssignmentType>(&json).unwrap(),
+            assignment_type
+        );
+    }
+
+    #[test]
+    fn test_decontract_carbon_basis_set() {
+        let input_stream = Cursor::new(CARBON_BASIS_SET);
+        let (_, basis_set) = read_basis_set(&mut input_stream.lines()).unwrap();
+
+        let decontracted = basis_set.decontract();
+        // The S and P exponentals of the SP shells are shared
+        assert_eq!(decontracted.get_num_contracted_functions(), 16);
+        assert_eq!(decontracted.get_num_gaussian_primitives(), 16);
+
+        let s_contractions: Vec<_> = decontracted
+            .into_iter()
+            .filter(|(am, _)| *am == AngularMomentum::S)
+            .map(|(_, contraction)| contraction)
+            .collect();
+        assert_eq!(s_contractions.len(), 11);
+        assert_abs_diff_eq!(s_contractions[6].get(0).unwrap().exponental(), 20.96420);
+        assert_abs_diff_eq!(s_contractions[6].get(0).unwrap().coefficient(), 1.0);
+    }
+
+    #[test]
+    fn test_unique_exponents() {
+        let input_stream = Cursor::new(CARBON_BASIS_SET);
+        let (_, basis_set) = read_basis_set(&mut input_stream.lines()).unwrap();
+
+        let s_exponentals = basis_set.unique_exponents(AngularMomentum::S);
+        assert_eq!(s_exponentals.len(), 11);
+        assert_abs_diff_eq!(s_exponentals[0], 4563.240);
+        assert_abs_diff_eq!(s_exponentals[6], 4.803310);
+        assert_abs_diff_eq!(s_exponentals[10], 0.1455850);
+        assert!(s_exponentals.windows(2).all(|pair| pair[0] > pair[1]));
+
+        assert_eq!(basis_set.unique_exponents(AngularMomentum::P).len(), 5);
+        assert!(basis_set.unique_exponents(AngularMomentum::D).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_exponents() {
+        let input = "C 0\nS 3 1.00\n 10.0 0.2\n 1.0 0.5\n 10.0 0.3\n****\n";
+
+        // Accepted unless asked for
+        let input_stream = Cursor::new(input);
+        assert!(read_basis_set(&mut input_stream.lines()).is_ok());
+
+        let config = ReaderConfig {
+            duplicate_exponent_tolerance: Some(1e-9),
+            ..Default::default()
+        };
+        let input_stream = Cursor::new(input);
+        let error = read_basis_set_with_config(&mut input_stream.lines(), &config)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::DuplicateExponents { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_write_unsupported_angular_momentum() {
+        use std::fmt::Write;
+
+        let basis_set = AtomicBasisSet::with_unsupported_slot();
+        let assignment = BasisSetAssignmentType::Atom("H".to_string());
+        let mut output = vec![];
+        let error = GaussianWriter::default()
+            .write_basis_set(&assignment, &basis_set, &mut output)
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(output.is_empty());
+        let mut text = String::new();
+        assert!(write!(text, "{}", basis_set).is_err());
+    }
+
+    #[test]
+    fn test_declaration_flag() {
+        for (declaration, assignment, flag) in [
+            ("C 0", BasisSetAssignmentType::Atom("C".to_string()), 0),
+            ("C 1", BasisSetAssignmentType::Atom("C".to_string()), 1),
+            ("C", BasisSetAssignmentType::Atom("C".to_string()), 0),
+            ("2 0", BasisSetAssignmentType::ParticleIndex(2), 0),
+            ("2 -1", BasisSetAssignmentType::ParticleIndex(2), -1),
+        ] {
+            let input = format!("{}\nS 1 1.00\n 0.5 1.0\n****\n", declaration);
+            let (read_assignment, basis_set) =
+                read_basis_set(&mut Cursor::new(input).lines()).unwrap();
+            assert_eq!(read_assignment, assignment);
+            assert_eq!(basis_set.get_declaration_flag(), flag);
+
+            let mut output = Vec::<u8>::new();
+            GaussianWriter::default()
+                .write_basis_set(&read_assignment, &basis_set, &mut output)
+                .unwrap();
+            let output = String::from_utf8(output).unwrap();
+            assert!(output.starts_with(&format!("{}     {}\n", assignment, flag)));
+            let (round_trip_assignment, round_trip) =
+                read_basis_set(&mut Cursor::new(output).lines()).unwrap();
+            assert_eq!(round_trip_assignment, assignment);
+            assert_eq!(round_trip, basis_set);
+        }
+    }
+
+    #[test]
+    fn test_comment_prefixes() {
+        let input =
+            "# STO-3G for H\n* from a made-up program\nH 0\n# hydrogen\nS 1 1.00\n 0.5 1.0\n****\n";
+        let config = ReaderConfig {
+            comment_prefixes: vec!["#".to_string(), "*".to_string()],
+            ..Default::default()
+        };
+        let (assignment_type, basis_set) =
+            read_basis_set_with_config(&mut Cursor::new(input).lines(), &config).unwrap();
+        assert_eq!(
+            assignment_type,
+            BasisSetAssignmentType::Atom("H".to_string())
+        );
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 1);
+
+        // `#` is not a comment by default
+        assert!(read_basis_set(&mut Cursor::new(input).lines()).is_err());
+    }
+
+    #[test]
+    fn test_spd_shell() {
+        // Gaussian94 rows hold the shared exponental first, then one coefficient per letter
+        let input = "Ti 0
+SPD   3   1.00
+     5.0000000              0.1000000              0.2000000              0.3000000
+     1.2000000              0.4000000              0.5000000              0.6000000
+     0.3000000              0.7000000              0.8000000              0.9000000
+****
+";
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(input).lines()).unwrap();
+        let shells = basis_set.combined_shells();
+        assert_eq!(shells.len(), 1);
+        assert_eq!(
+            shells[0].0,
+            vec![AngularMomentum::S, AngularMomentum::P, AngularMomentum::D]
+        );
+
+        let contractions: Vec<_> = basis_set.into_iter().collect();
+        let expected_coefficients = [[0.1, 0.4, 0.7], [0.2, 0.5, 0.8], [0.3, 0.6, 0.9]];
+        assert_eq!(contractions.len(), 3);
+        for (index, (angular_momentum, contraction)) in contractions.iter().enumerate() {
+            assert_eq!(Ok(*angular_momentum), AngularMomentum::try_from(index));
+            assert!(contraction.shares_exponents(contractions[0].1));
+            assert_eq!(
+                contraction.coefficients().collect::<Vec<_>>(),
+                expected_coefficients[index]
+            );
+        }
+        assert_eq!(
+            contractions[2].1.exponents().collect::<Vec<_>>(),
+            vec![5.0, 1.2, 0.3]
+        );
+
+        // Written back as one SPD shell
+        assert!(basis_set
+            .to_string()
+            .starts_with("X     0\nSPD    3   1.00\n"));
+    }
+
+    #[test]
+    fn test_l_shell() {
+        let input = OXYGEN_BASIS_SET.replacen("SP   3", "L   3", 1);
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(&input).lines()).unwrap();
+        let (_, expected) = read_basis_set(&mut Cursor::new(OXYGEN_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_set, expected);
+        assert_eq!(
+            basis_set.primitives_per_angular_momentum(),
+            vec![(AngularMomentum::S, 6), (AngularMomentum::P, 3)]
+        );
+        assert_eq!(
+            basis_set.combined_shells()[1].0,
+            vec![AngularMomentum::S, AngularMomentum::P]
+        );
+
+        let lower_case = OXYGEN_BASIS_SET.replacen("SP   3", "l   3", 1);
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(lower_case).lines()).unwrap();
+        assert_eq!(basis_set, expected);
+
+        // An S and a P coefficient are required
+        let error = read_basis_set(&mut Cursor::new("O 0\nL 1 1.00\n 0.5 1.0\n****\n").lines())
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::BadColumnCount { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_cgto_scale_factor() {
+        // STO-3G for H, from the exponentals of the 1s function with a scale factor of 1.24
+        let input = "H 0
+S    3   1.24
+      2.22766058           0.15432897
+      0.40577116           0.53532814
+      0.10981751           0.44463454
+****
+";
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(input).lines()).unwrap();
+        let (_, contraction) = basis_set.into_iter().next().unwrap();
+        for (exponental, expected) in contraction
+            .exponents()
+            .zip([3.42525091, 0.62391373, 0.1688554])
+        {
+            assert_abs_diff_eq!(exponental, expected, epsilon = 1e-7);
+        }
+        assert_eq!(contraction.get(0).unwrap().coefficient(), 0.15432897);
+        assert!(basis_set
+            .to_string()
+            .starts_with("X     0\nS    3   1.00\n"));
+    }
+
+    #[test]
+    fn test_require_terminator() {
+        let truncated = OXYGEN_BASIS_SET.replace("****\n", "");
+        let config = ReaderConfig {
+            require_terminator: true,
+            ..Default::default()
+        };
+        let error = read_basis_set_with_config(&mut Cursor::new(&truncated).lines(), &config)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::UnexpectedEof)
+        ));
+
+        // Accepted by default, and always with the terminator
+        let (_, basis_set) = read_basis_set(&mut Cursor::new(&truncated).lines()).unwrap();
+        assert_eq!(basis_set.get_num_contracted_functions(), 3);
+        let (_, basis_set) =
+            read_basis_set_with_config(&mut Cursor::new(OXYGEN_BASIS_SET).lines(), &config)
+                .unwrap();
+        assert_eq!(basis_set.get_num_contracted_functions(), 3);
+    }
+
+    #[test]
+    fn test_continuation_rows() {
+        let input = "Cu 0
+D    3   1.00
+     48.9750000              0.0313260              0.0000000
+                             0.0100000
+     13.3600000              0.1599200              0.0000000
+                             0.0200000
+      4.3990000              0.3945700             -0.2314500              0.5000000
+SP   2   1.00
+      3.0 0.1
+      0.2
+      0.5 0.9 0.8
+****
+";
+        let config = ReaderConfig {
+            continuation_rows: true,
+            ..Default::default()
+        };
+        let (_, basis_set) =
+            read_basis_set_with_config(&mut Cursor::new(input).lines(), &config).unwrap();
+        assert_eq!(basis_set.get_num_contracted_functions(), 5);
+        let shells = basis_set.combined_shells();
+        assert_eq!(shells.len(), 2);
+        // Shells are ordered by angular momentum, hence SP first
+        assert_eq!(shells[1].0, vec![AngularMomentum::D; 3]);
+        assert_eq!(
+            shells[1].1[0].exponents().collect::<Vec<_>>(),
+            vec![48.975, 13.36, 4.399]
+        );
+        assert_eq!(
+            shells[1].1[2].coefficients().collect::<Vec<_>>(),
+            vec![0.01, 0.02, 0.5]
+        );
+        assert_eq!(
+            shells[0].1[1].coefficients().collect::<Vec<_>>(),
+            vec![0.2, 0.8]
+        );
+
+        // Rows are still read one line at a time by default
+        assert!(read_basis_set(&mut Cursor::new(input).lines()).is_err());
+
+        // Values that do not split evenly between the primitives
+        let error = read_basis_set_with_config(
+            &mut Cursor::new("Cu 0\nD 2 1.00\n 3.0 0.1 0.2\n 0.5 0.9\n****\n").lines(),
+            &config,
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::BadColumnCount { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_basis_set_with_metadata() {
+        let input_stream = Cursor::new(CARBON_BASIS_SET);
+
+        let (comments, assignment_type, basis_set) =
+            read_basis_set_with_metadata(&mut input_stream.lines()).unwrap();
+        assert_eq!(comments.len(), 10);
+        assert_eq!(comments[1], "Basis Set Exchange");
+        assert!(comments.contains(&"Basis set: 6-311G".to_string()));
+        assert_eq!(
+            assignment_type,
+            BasisSetAssignmentType::Atom("C".to_string())
+        );
+        assert_eq!(basis_set.get_num_contracted_functions(), 7);
+    }
+
+    #[test]
+    fn test_bad_column_count() {
+        for (input, expected_line) in [
+            // SPD shell with only S and P coefficients
+            ("C 0\nSPD 2 1.00\n 3.0 0.1 0.2\n 0.5 0.9 0.8\n****\n", 3),
+            ("C 0\nSP 2 1.00\n 3.0 0.1 0.2\n 0.5 0.9 0.8 0.7\n****\n", 4),
+            ("C 0\nS 2 1.00\n 3.0 0.1 0.2\n 0.5 0.9\n****\n", 4),
+            ("C 0\nS 1 1.00\n 3.0\n****\n", 3),
+        ] {
+            let error = read_basis_set(&mut Cursor::new(input).lines())
+                .err()
+                .unwrap();
+            match error.downcast_ref::<BasisSetParseError>() {
+                Some(BasisSetParseError::BadColumnCount { line, .. }) => {
+                    assert_eq!(*line, expected_line)
+                }
+                _ => panic!("Unexpected error {}", error),
+            }
+        }
+    }
+
+    #[test]
+    fn test_unknown_angular_momentum() {
+        let input_stream = Cursor::new("C 0\nT 1 1.00\n 1.0 1.0\n****\n");
+
+        let error = read_basis_set(&mut input_stream.lines()).err().unwrap();
+        match error.downcast_ref::<BasisSetParseError>() {
+            Some(BasisSetParseError::UnknownAngularMomentum { line, .. }) => assert_eq!(*line, 2),
+            _ => panic!("Unexpected error {}", error),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_line_number() {
+        // The comment and blank lines are counted
+        let input_stream = Cursor::new("! Comment\nC 0\n\nS 2 1.00\n 10.0 0.5\n 1.0 err\n****\n");
+
+        let error = read_basis_set(&mut input_stream.lines()).err().unwrap();
+        match error.downcast_ref::<BasisSetParseError>() {
+            Some(BasisSetParseError::BadFloat { line, text }) => {
+                assert_eq!(*line, 6);
+                assert_eq!(text, " 1.0 err");
+            }
+            _ => panic!("Unexpected error {}", error),
+        }
+        assert_eq!(error.to_string(), "Line 6: bad primitive values ' 1.0 err'");
+
+        let truncated_stream = Cursor::new("C 0\nS 2 1.00\n 10.0 0.5\n");
+        let error = read_basis_set(&mut truncated_stream.lines()).err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::UnexpectedEof)
+        ));
+    }
 }
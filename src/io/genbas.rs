@@ -0,0 +1,220 @@
+use std::{collections::VecDeque, error::Error};
+
+use super::BasisSetParseError;
+use crate::details::{
+    angular_momentum::AngularMomentum, atomic_basis_set::AtomicBasisSet,
+    basis_set_assignment::BasisSetAssignmentType,
+};
+
+/// Reads a GENBAS entry value by value, as the integer and float blocks wrap freely over lines
+struct TokenReader<'a> {
+    stream: &'a mut dyn Iterator<Item = Result<String, std::io::Error>>,
+    line_number: usize,
+    tokens: VecDeque<String>,
+}
+
+impl<'a> TokenReader<'a> {
+    fn new(stream: &'a mut dyn Iterator<Item = Result<String, std::io::Error>>) -> Self {
+        TokenReader {
+            stream,
+            line_number: 0,
+            tokens: VecDeque::new(),
+        }
+    }
+
+    fn next_line(&mut self) -> Result<Option<String>, BasisSetParseError> {
+        match self.stream.next() {
+            Some(item) => {
+                self.line_number += 1;
+                Ok(Some(item.map_err(BasisSetParseError::Io)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_token(&mut self) -> Result<String, BasisSetParseError> {
+        while self.tokens.is_empty() {
+            let line = self.next_line()?.ok_or(BasisSetParseError::UnexpectedEof)?;
+            self.tokens
+                .extend(line.split_whitespace().map(|token| token.to_string()));
+        }
+        Ok(self.tokens.pop_front().unwrap())
+    }
+
+    fn next_usize(&mut self) -> Result<usize, BasisSetParseError> {
+        let token = self.next_token()?;
+        token
+            .parse()
+            .map_err(|_| BasisSetParseError::BadCgtoHeader {
+                line: self.line_number,
+                text: token,
+            })
+    }
+
+    fn next_f64(&mut self) -> Result<f64, BasisSetParseError> {
+        let token = self.next_token()?;
+        token.parse().map_err(|_| BasisSetParseError::BadFloat {
+            line: self.line_number,
+            text: token,
+        })
+    }
+}
+
+fn read_usize_block(
+    reader: &mut TokenReader,
+    num_shells: usize,
+) -> Result<Vec<usize>, BasisSetParseError> {
+    (0..num_shells).map(|_| reader.next_usize()).collect()
+}
+
+/// Reads the entry following its `H:STO-3G` style header line
+fn read_genbas_entry(reader: &mut TokenReader) -> Result<AtomicBasisSet, BasisSetParseError> {
+    // The line after the header is a free-form comment
+    reader
+        .next_line()?
+        .ok_or(BasisSetParseError::UnexpectedEof)?;
+
+    // Number of shells, then their angular momentums, numbers of contracted functions and
+    // numbers of primitives, each as a block of integers
+    let num_shells = reader.next_usize()?;
+    let angular_momentums = read_usize_block(reader, num_shells)?;
+    let num_contracted_functions = read_usize_block(reader, num_shells)?;
+    let num_primitives = read_usize_block(reader, num_shells)?;
+
+    let mut basis_set = AtomicBasisSet::new();
+    for shell in 0..num_shells {
+        let angular_momentum =
+            AngularMomentum::try_from(angular_momentums[shell]).map_err(|_| {
+                BasisSetParseError::UnknownAngularMomentum {
+                    line: reader.line_number,
+                    text: angular_momentums[shell].to_string(),
+                }
+            })?;
+        // Exponentals, then the coefficient matrix with one row per primitive and one column
+        // per contracted function
+        let mut rows = vec![];
+        for _ in 0..num_primitives[shell] {
+            rows.push(vec![reader.next_f64()?]);
+        }
+        for row in rows.iter_mut() {
+            for _ in 0..num_contracted_functions[shell] {
+                row.push(reader.next_f64()?);
+            }
+        }
+        // A shell without contracted functions leaves its rows with the exponental alone
+        basis_set
+            .add_combined_shell(&[angular_momentum], &rows)
+            .map_err(|_| BasisSetParseError::BadColumnCount {
+                line: reader.line_number,
+                text: num_contracted_functions[shell].to_string(),
+            })?;
+    }
+
+    if let Some(token) = reader.tokens.pop_front() {
+        return Err(BasisSetParseError::BadFloat {
+            line: reader.line_number,
+            text: token,
+        });
+    }
+    Ok(basis_set)
+}
+
+/// Reads the entries of a CFOUR `GENBAS` file, returning one basis set per entry. An entry
+/// starts with the element and the basis set name, e.g. `H:STO-3G`, and a comment line. The
+/// number of shells follows, then blocks of their angular momentums, numbers of contracted
+/// functions and numbers of primitives. Each shell lists its exponentals, then its coefficient
+/// matrix with one row per primitive.
+pub fn read_basis_set(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let mut reader = TokenReader::new(stream);
+    let mut basis_sets = vec![];
+
+    while let Some(line) = reader.next_line()? {
+        let header = line.trim();
+        if header.is_empty() || header.starts_with('!') {
+            continue;
+        }
+        let element = match header.split_once(':') {
+            Some((element, _)) if !element.is_empty() => element,
+            _ => {
+                return Err(Box::new(BasisSetParseError::BadDeclaration {
+                    line: reader.line_number,
+                    text: line,
+                }))
+            }
+        };
+        let assignment = BasisSetAssignmentType::Atom(element.to_string());
+        basis_sets.push((assignment, read_genbas_entry(&mut reader)?));
+    }
+    Ok(basis_sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::details::{
+        angular_momentum::AngularMomentum, basis_set_assignment::BasisSetAssignmentType,
+    };
+    use crate::io::BasisSetParseError;
+
+    use super::read_basis_set;
+
+    // 6-31G* for H with a made-up two-function contraction for the S shell
+    const HYDROGEN_BASIS_SET: &str = "H:6-31G*
+6-31G* for hydrogen
+
+  2
+    0    1
+    2    1
+    4    1
+
+   18.7311370   2.8253937   0.6401217
+    0.1612778
+
+    0.0334946    0.0000000
+    0.2347270    0.0000000
+    0.8137573    0.0000000
+    0.0000000    1.0000000
+
+    1.1000000
+
+    1.0000000
+
+";
+
+    #[test]
+    fn test_read_hydrogen_basis_set() {
+        let basis_sets = read_basis_set(&mut Cursor::new(HYDROGEN_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_sets.len(), 1);
+        let (assignment, basis_set) = &basis_sets[0];
+        assert_eq!(*assignment, BasisSetAssignmentType::Atom("H".to_string()));
+        assert_eq!(basis_set.get_num_contracted_functions(), 3);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 9);
+        assert_eq!(basis_set.get_highest_angular_momentum(), AngularMomentum::P);
+
+        // The 4x2 coefficient matrix of the S shell
+        let contractions: Vec<_> = basis_set.into_iter().collect();
+        assert_eq!(contractions[0].1.get_num_primitives(), 4);
+        assert_eq!(contractions[1].1.get_num_primitives(), 4);
+        assert_abs_diff_eq!(contractions[0].1.get(3).unwrap().exponental(), 0.1612778);
+        assert_abs_diff_eq!(contractions[0].1.get(1).unwrap().coefficient(), 0.234727);
+        assert_abs_diff_eq!(contractions[1].1.get(3).unwrap().coefficient(), 1.0);
+        assert_eq!(contractions[2].0, AngularMomentum::P);
+        assert_abs_diff_eq!(contractions[2].1.get(0).unwrap().exponental(), 1.1);
+    }
+
+    #[test]
+    fn test_read_truncated_entry() {
+        let error = read_basis_set(&mut Cursor::new("H:STO-3G\ncomment\n 1\n 0\n 1\n 3\n").lines())
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::UnexpectedEof)
+        ));
+    }
+}
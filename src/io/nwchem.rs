@@ -0,0 +1,318 @@
+use std::{error::Error, io::Write};
+
+use super::{writer::check_angular_momentums, BasisSetParseError};
+use crate::details::{
+    angular_momentum::AngularMomentum,
+    atomic_basis_set::{AtomicBasisSet, BasisSetValidationError, SphericalOrCartesian},
+    basis_set_assignment::BasisSetAssignmentType,
+};
+
+/// Shell being read, the rows are only complete once the next header or `END` shows up
+struct PendingShell {
+    assignment: BasisSetAssignmentType,
+    angular_momentums: Vec<AngularMomentum>,
+    rows: Vec<Vec<f64>>,
+}
+
+fn add_pending_shell(
+    basis_sets: &mut Vec<(BasisSetAssignmentType, AtomicBasisSet)>,
+    shell: PendingShell,
+    spherical: SphericalOrCartesian,
+) -> Result<(), BasisSetValidationError> {
+    let index = match basis_sets
+        .iter()
+        .position(|(assignment, _)| *assignment == shell.assignment)
+    {
+        Some(index) => index,
+        None => {
+            basis_sets.push((shell.assignment, AtomicBasisSet::new()));
+            basis_sets.len() - 1
+        }
+    };
+    basis_sets[index]
+        .1
+        .set_spherical(spherical)
+        .add_combined_shell(&shell.angular_momentums, &shell.rows)?;
+    Ok(())
+}
+
+fn parse_shell_header(line: &str, line_number: usize) -> Result<PendingShell, BasisSetParseError> {
+    // Element symbol and shell letters, e.g. "H    S" or "C    SP"
+    let split: Vec<&str> = line.split_whitespace().collect();
+    if split.len() != 2 {
+        return Err(BasisSetParseError::BadCgtoHeader {
+            line: line_number,
+            text: line.to_string(),
+        });
+    }
+    let angular_momentums = split[1]
+        .chars()
+        .map(AngularMomentum::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| BasisSetParseError::UnknownAngularMomentum {
+            line: line_number,
+            text: line.to_string(),
+        })?;
+    Ok(PendingShell {
+        assignment: BasisSetAssignmentType::Atom(split[0].to_string()),
+        angular_momentums,
+        rows: vec![],
+    })
+}
+
+/// Reads the `BASIS ... END` blocks of an NWChem input, returning one basis set per element
+/// in the order they first appear. Shells have no primitive count, the rows of exponentals
+/// followed by coefficients run until the next shell header or `END`. The basis sets are
+/// Cartesian unless the `BASIS` line asks for `SPHERICAL`, as in NWChem.
+pub fn read_basis_set(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let mut basis_sets = vec![];
+    let mut in_basis_block = false;
+    let mut spherical = SphericalOrCartesian::Cartesian;
+    let mut pending_shell: Option<PendingShell> = None;
+
+    for (index, item) in stream.enumerate() {
+        let line_number = index + 1;
+        let line = item.map_err(BasisSetParseError::Io)?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let keyword = trimmed.split_whitespace().next().unwrap_or_default();
+
+        if !in_basis_block {
+            in_basis_block = keyword.eq_ignore_ascii_case("BASIS");
+            spherical = if trimmed
+                .split_whitespace()
+                .any(|word| word.eq_ignore_ascii_case("SPHERICAL"))
+            {
+                SphericalOrCartesian::Spherical
+            } else {
+                SphericalOrCartesian::Cartesian
+            };
+            continue;
+        }
+        if keyword.eq_ignore_ascii_case("END") {
+            if let Some(shell) = pending_shell.take() {
+                add_pending_shell(&mut basis_sets, shell, spherical)?;
+            }
+            in_basis_block = false;
+        } else if keyword.parse::<f64>().is_ok() {
+            let row = trimmed
+                .split_whitespace()
+                .map(|value| value.parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| BasisSetParseError::BadFloat {
+                    line: line_number,
+                    text: line.clone(),
+                })?;
+            match pending_shell.as_mut() {
+                Some(shell) => {
+                    // An exponental and a coefficient, one per letter of combined shells such
+                    // as SP, any number of them for general contractions as long as every row
+                    // of the shell agrees
+                    let num_letters = shell.angular_momentums.len();
+                    let num_columns_expected = match shell.rows.first() {
+                        Some(first_row) => row.len() == first_row.len(),
+                        None => {
+                            row.len() >= 2 && (num_letters == 1 || row.len() == num_letters + 1)
+                        }
+                    };
+                    if !num_columns_expected {
+                        return Err(Box::new(BasisSetParseError::BadColumnCount {
+                            line: line_number,
+                            text: line,
+                        }));
+                    }
+                    shell.rows.push(row);
+                }
+                None => {
+                    return Err(Box::new(BasisSetParseError::BadCgtoHeader {
+                        line: line_number,
+                        text: line,
+                    }))
+                }
+            }
+        } else {
+            if let Some(shell) = pending_shell.take() {
+                add_pending_shell(&mut basis_sets, shell, spherical)?;
+            }
+            pending_shell = Some(parse_shell_header(&line, line_number)?);
+        }
+    }
+
+    if in_basis_block {
+        return Err(Box::new(BasisSetParseError::UnexpectedEof));
+    }
+    Ok(basis_sets)
+}
+
+/// Writes the basis set as an NWChem `BASIS` block, e.g.
+///
+/// ```text
+/// BASIS "ao basis" SPHERICAL PRINT
+/// C    S
+///              4563.24           0.00196665
+/// ...
+/// END
+/// ```
+///
+/// Every shell is written with its own header, so the S and P parts of SP shells appear as
+/// separate S and P shells. Rows hold the exponental followed by the coefficient. `SPHERICAL`
+/// or `CARTESIAN` is written out following the basis set.
+pub fn write_basis_set(
+    assignment: &BasisSetAssignmentType,
+    basis_set: &AtomicBasisSet,
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    check_angular_momentums(basis_set)?;
+    let spherical = match basis_set.get_spherical() {
+        SphericalOrCartesian::Spherical => "SPHERICAL",
+        SphericalOrCartesian::Cartesian => "CARTESIAN",
+    };
+    writeln!(writer, "BASIS \"ao basis\" {} PRINT", spherical)?;
+    for (angular_momentum, segmented_contraction) in basis_set {
+        writeln!(writer, "{}    {}", assignment, angular_momentum.symbol())?;
+        for primitive in segmented_contraction {
+            writeln!(
+                writer,
+                "{:>20} {:>20}",
+                primitive.exponental(),
+                primitive.coefficient()
+            )?;
+        }
+    }
+    writeln!(writer, "END")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::details::{
+        angular_momentum::AngularMomentum,
+        atomic_basis_set::{AtomicBasisSet, SphericalOrCartesian},
+        basis_set_assignment::BasisSetAssignmentType,
+        gaussian_exp::SegmentedContraction,
+    };
+
+    use std::io::{BufRead, Cursor};
+
+    use approx::assert_abs_diff_eq;
+
+    use super::{read_basis_set, write_basis_set, BasisSetParseError};
+
+    #[test]
+    fn test_write_basis_set() {
+        // 6-31G** for H
+        let mut s_contraction = SegmentedContraction::new();
+        s_contraction
+            .add(0.03349460, 18.7311370)
+            .add(0.23472695, 2.8253937)
+            .add(0.81375733, 0.6401217);
+        let mut diffuse_s_contraction = SegmentedContraction::new();
+        diffuse_s_contraction.add(1.0, 0.1612778);
+        let mut p_contraction = SegmentedContraction::new();
+        p_contraction.add(1.0, 1.1);
+
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_segmented_contraction(AngularMomentum::P, p_contraction)
+            .unwrap()
+            .add_segmented_contraction(AngularMomentum::S, s_contraction)
+            .unwrap()
+            .add_segmented_contraction(AngularMomentum::S, diffuse_s_contraction)
+            .unwrap();
+
+        let mut output = Vec::<u8>::new();
+        write_basis_set(
+            &BasisSetAssignmentType::Atom("H".to_string()),
+            &basis_set,
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "BASIS \"ao basis\" SPHERICAL PRINT
+H    S
+           18.731137            0.0334946
+           2.8253937           0.23472695
+           0.6401217           0.81375733
+H    S
+           0.1612778                    1
+H    P
+                 1.1                    1
+END
+"
+        );
+    }
+
+    #[test]
+    fn test_read_basis_set() {
+        let input_stream = Cursor::new(
+            "# 6-31G for H
+BASIS \"ao basis\" PRINT
+#BASIS SET: (4s) -> [2s]
+H    S
+     18.7311370              0.03349460
+      2.8253937              0.23472695
+      0.6401217              0.81375733
+H    S
+      0.1612778              1.0000000
+END
+",
+        );
+
+        let basis_sets = read_basis_set(&mut input_stream.lines()).unwrap();
+        assert_eq!(basis_sets.len(), 1);
+        let (assignment, basis_set) = &basis_sets[0];
+        assert_eq!(*assignment, BasisSetAssignmentType::Atom("H".to_string()));
+        assert_eq!(basis_set.get_num_contracted_functions(), 2);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 4);
+        assert_eq!(basis_set.get_spherical(), SphericalOrCartesian::Cartesian);
+
+        let contractions: Vec<_> = basis_set.into_iter().collect();
+        assert_eq!(contractions[0].0, AngularMomentum::S);
+        assert_abs_diff_eq!(contractions[0].1.get(1).unwrap().exponental(), 2.8253937);
+        assert_abs_diff_eq!(contractions[0].1.get(1).unwrap().coefficient(), 0.23472695);
+        assert_abs_diff_eq!(contractions[1].1.get(0).unwrap().exponental(), 0.1612778);
+    }
+
+    #[test]
+    fn test_spherical_or_cartesian() {
+        let input_stream = Cursor::new("BASIS \"ao basis\" SPHERICAL\nH S\n 1.1 1.0\nEND\n");
+        let mut basis_sets = read_basis_set(&mut input_stream.lines()).unwrap();
+        let (assignment, basis_set) = &mut basis_sets[0];
+        assert_eq!(basis_set.get_spherical(), SphericalOrCartesian::Spherical);
+
+        basis_set.set_spherical(SphericalOrCartesian::Cartesian);
+        let mut output = Vec::<u8>::new();
+        write_basis_set(assignment, basis_set, &mut output).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("BASIS \"ao basis\" CARTESIAN PRINT\n"));
+    }
+
+    #[test]
+    fn test_read_basis_set_errors() {
+        let missing_end = Cursor::new("BASIS\nH S\n 1.0 1.0\n");
+        assert!(read_basis_set(&mut missing_end.lines()).is_err());
+
+        let unknown_shell = Cursor::new("BASIS\nH X\n 1.0 1.0\nEND\n");
+        assert!(read_basis_set(&mut unknown_shell.lines()).is_err());
+
+        for (input, line_number) in [
+            ("BASIS\nH SP\n 1.0 0.5\nEND\n", 3),
+            ("BASIS\nH S\n 1.0\nEND\n", 3),
+            ("BASIS\nH S\n 1.0 0.5 0.3\n 2.0 0.4\nEND\n", 4),
+        ] {
+            let error = read_basis_set(&mut Cursor::new(input).lines())
+                .err()
+                .unwrap();
+            assert!(matches!(
+                error.downcast_ref::<BasisSetParseError>(),
+                Some(BasisSetParseError::BadColumnCount { line, .. }) if *line == line_number
+            ));
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::error::Error;
+
+use super::{
+    gamess_shell::{parse_primitive_row, parse_shell_header, PendingShell},
+    BasisSetParseError,
+};
+use crate::details::{
+    atomic_basis_set::AtomicBasisSet, basis_set_assignment::BasisSetAssignmentType,
+};
+
+/// Position within the nested `%basis ... end` and `NewGTO ... end` blocks
+#[derive(PartialEq)]
+enum Block {
+    // Outside of `%basis`, e.g. among the other input blocks
+    Outside,
+    Basis,
+    NewGto,
+    // Any other `New...` block of `%basis`, e.g. `NewECP` or `NewAuxJGTO`
+    Skipped,
+}
+
+fn parse_element(
+    line: &str,
+    line_number: usize,
+) -> Result<BasisSetAssignmentType, BasisSetParseError> {
+    // The element follows the keyword either as its symbol or as its atomic number
+    let element =
+        line.split_whitespace()
+            .nth(1)
+            .ok_or_else(|| BasisSetParseError::BadDeclaration {
+                line: line_number,
+                text: line.to_string(),
+            })?;
+    match element.parse::<usize>() {
+        Ok(atomic_number) => BasisSetAssignmentType::from_atomic_number(atomic_number).ok_or(
+            BasisSetParseError::BadDeclaration {
+                line: line_number,
+                text: line.to_string(),
+            },
+        ),
+        Err(_) => Ok(BasisSetAssignmentType::Atom(element.to_string())),
+    }
+}
+
+/// Reads the `NewGTO` sections of the `%basis ... end` blocks of an ORCA input, returning one
+/// basis set per section. A section starts with `NewGTO` and the element symbol or atomic
+/// number and closes with `end`. Shells hold a `S   3` style header followed by rows of
+/// primitive index, exponental and coefficients, `L` shells an S and a P coefficient.
+/// Keywords are case insensitive and `#` starts a comment. Other blocks of the input, and
+/// other `New...` sections such as `NewECP`, are skipped.
+pub fn read_basis_set(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let mut basis_sets: Vec<(BasisSetAssignmentType, AtomicBasisSet)> = vec![];
+    let mut block = Block::Outside;
+    let mut pending_shell: Option<PendingShell> = None;
+
+    for (index, item) in stream.enumerate() {
+        let line_number = index + 1;
+        let line = item.map_err(BasisSetParseError::Io)?;
+        let trimmed = line.split('#').next().unwrap_or_default().trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let keyword = trimmed
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if let Some(shell) = pending_shell.as_mut() {
+            // An exponental and one coefficient per angular momentum
+            let num_columns = shell.angular_momentums.len() + 1;
+            shell
+                .rows
+                .push(parse_primitive_row(trimmed, line_number, num_columns)?);
+            if shell.rows.len() == shell.num_primitives {
+                let shell = pending_shell.take().unwrap();
+                let (_, basis_set) = basis_sets.last_mut().unwrap();
+                basis_set.add_combined_shell(&shell.angular_momentums, &shell.rows)?;
+            }
+            continue;
+        }
+
+        block = match block {
+            Block::Outside if keyword == "%basis" => Block::Basis,
+            Block::Outside => Block::Outside,
+            Block::Basis if keyword == "end" => Block::Outside,
+            Block::Basis if keyword == "newgto" => {
+                basis_sets.push((parse_element(trimmed, line_number)?, AtomicBasisSet::new()));
+                Block::NewGto
+            }
+            Block::Basis if keyword.starts_with("new") => Block::Skipped,
+            Block::Basis => Block::Basis,
+            Block::NewGto if keyword == "end" => Block::Basis,
+            Block::NewGto => {
+                let shell = parse_shell_header(trimmed, line_number)?;
+                if shell.num_primitives > 0 {
+                    pending_shell = Some(shell);
+                }
+                Block::NewGto
+            }
+            Block::Skipped if keyword == "end" => Block::Basis,
+            Block::Skipped => Block::Skipped,
+        };
+    }
+
+    if pending_shell.is_some() || block != Block::Outside {
+        return Err(Box::new(BasisSetParseError::UnexpectedEof));
+    }
+    Ok(basis_sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::details::{
+        angular_momentum::AngularMomentum, basis_set_assignment::BasisSetAssignmentType,
+    };
+    use crate::io::BasisSetParseError;
+
+    use super::read_basis_set;
+
+    // 6-31G* for H, from the Basis Set Exchange, along with an unrelated block, an ECP and a
+    // made-up L shell for C given by its atomic number
+    const HYDROGEN_BASIS_SET: &str = "! HF 6-31G*
+%scf
+  MaxIter 100
+end
+%basis
+  # H 6-31G*
+  NewGTO H
+    S   3
+      1        18.7311370              0.03349460
+      2         2.8253937              0.23472695
+      3         0.6401217              0.81375733
+    S   1
+      1         0.1612778              1.0000000
+  end
+  NewECP 53
+    N_core 28
+    lmax f
+    s 1
+      1      1.0000000      0.0000000 2
+  end
+  newgto 6
+    L   1
+      1         0.1687144              1.0000000              1.0000000
+  END
+end
+";
+
+    #[test]
+    fn test_read_hydrogen_basis_set() {
+        let basis_sets = read_basis_set(&mut Cursor::new(HYDROGEN_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_sets.len(), 2);
+        let (assignment, basis_set) = &basis_sets[0];
+        assert_eq!(*assignment, BasisSetAssignmentType::Atom("H".to_string()));
+        assert_eq!(basis_set.get_num_contracted_functions(), 2);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 4);
+        assert_eq!(basis_set.get_highest_angular_momentum(), AngularMomentum::S);
+
+        let contractions: Vec<_> = basis_set.into_iter().collect();
+        assert_abs_diff_eq!(contractions[0].1.get(0).unwrap().exponental(), 18.731137);
+        assert_abs_diff_eq!(contractions[0].1.get(2).unwrap().coefficient(), 0.81375733);
+        assert_abs_diff_eq!(contractions[1].1.get(0).unwrap().exponental(), 0.1612778);
+
+        // Atomic number and an L shell
+        let (assignment, basis_set) = &basis_sets[1];
+        assert_eq!(*assignment, BasisSetAssignmentType::Atom("C".to_string()));
+        assert_eq!(basis_set.get_num_contracted_functions(), 2);
+        assert_eq!(basis_set.get_highest_angular_momentum(), AngularMomentum::P);
+    }
+
+    #[test]
+    fn test_read_unclosed_block() {
+        let error =
+            read_basis_set(&mut Cursor::new("%basis\nNewGTO H\nS 1\n1 0.5 1.0\nend\n").lines())
+                .err()
+                .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn test_read_bad_column_count() {
+        let error = read_basis_set(
+            &mut Cursor::new("%basis\nNewGTO H\nL 1\n1 0.5 1.0\nend\nend\n").lines(),
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::BadColumnCount { line: 4, .. })
+        ));
+    }
+}
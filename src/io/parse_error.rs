@@ -0,0 +1,67 @@
+use std::error::Error;
+
+#[derive(Debug)]
+pub enum BasisSetParseError {
+    // The stream ended before the basis set was complete
+    UnexpectedEof,
+    // The underlying stream failed
+    Io(std::io::Error),
+    // Bad atom/particle index declaration
+    BadDeclaration { line: usize, text: String },
+    // Bad angular momentum/number of primitives declaration
+    BadCgtoHeader { line: usize, text: String },
+    // Angular momentum letter that is not supported
+    UnknownAngularMomentum { line: usize, text: String },
+    // Bad primitive row
+    BadFloat { line: usize, text: String },
+    // Primitive row with a number of columns not matching the shell
+    BadColumnCount { line: usize, text: String },
+    // Shell repeating an exponental, only reported when asked for
+    DuplicateExponents { line: usize, text: String },
+    // Bad effective core potential declaration or term
+    BadEcp { line: usize, text: String },
+    // Bad shell in formats without meaningful line numbers, specified by the index starting with 0
+    BadShell { index: usize, text: String },
+}
+
+impl std::fmt::Display for BasisSetParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BasisSetParseError::UnexpectedEof => write!(f, "Unexpected end of basis set"),
+            BasisSetParseError::Io(error) => write!(f, "Failed to read basis set: {}", error),
+            BasisSetParseError::BadDeclaration { line, text } => {
+                write!(f, "Line {}: bad basis set declaration '{}'", line, text)
+            }
+            BasisSetParseError::BadCgtoHeader { line, text } => {
+                write!(f, "Line {}: bad CGTO declaration '{}'", line, text)
+            }
+            BasisSetParseError::UnknownAngularMomentum { line, text } => {
+                write!(f, "Line {}: unknown angular momentum in '{}'", line, text)
+            }
+            BasisSetParseError::BadFloat { line, text } => {
+                write!(f, "Line {}: bad primitive values '{}'", line, text)
+            }
+            BasisSetParseError::BadColumnCount { line, text } => {
+                write!(f, "Line {}: unexpected number of columns '{}'", line, text)
+            }
+            BasisSetParseError::DuplicateExponents { line, text } => {
+                write!(f, "Line {}: duplicate exponents in shell '{}'", line, text)
+            }
+            BasisSetParseError::BadEcp { line, text } => {
+                write!(f, "Line {}: bad ECP data '{}'", line, text)
+            }
+            BasisSetParseError::BadShell { index, text } => {
+                write!(f, "Shell {}: {}", index, text)
+            }
+        }
+    }
+}
+
+impl Error for BasisSetParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BasisSetParseError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::io::Write;
+
+use super::{gaussian::GaussianWriter, BasisSetWriter};
+use crate::details::{
+    atomic_basis_set::{AtomicBasisSet, SphericalOrCartesian},
+    basis_set_assignment::BasisSetAssignmentType,
+};
+
+/// Writes the basis set as a Psi4 `.gbs` file, e.g.
+///
+/// ```text
+/// spherical
+///
+/// ****
+/// H     0
+/// S    3   1.00
+/// ...
+/// ****
+/// ```
+///
+/// The first line holds the `spherical` or `cartesian` directive taken from the basis set,
+/// the atom block follows in the Gaussian94 layout between `****` lines.
+pub fn write_basis_set(
+    assignment: &BasisSetAssignmentType,
+    basis_set: &AtomicBasisSet,
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    let directive = match basis_set.get_spherical() {
+        SphericalOrCartesian::Spherical => "spherical",
+        SphericalOrCartesian::Cartesian => "cartesian",
+    };
+    writeln!(writer, "{}", directive)?;
+    writeln!(writer)?;
+    writeln!(writer, "****")?;
+    GaussianWriter::default().write_basis_set(assignment, basis_set, writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::details::{
+        angular_momentum::AngularMomentum,
+        atomic_basis_set::{AtomicBasisSet, SphericalOrCartesian},
+        basis_set_assignment::BasisSetAssignmentType,
+    };
+
+    use super::write_basis_set;
+
+    #[test]
+    fn test_write_hydrogen_basis_set() {
+        // STO-3G for H
+        let mut basis_set = AtomicBasisSet::new();
+        basis_set
+            .add_shell(
+                AngularMomentum::S,
+                &[0.15432897, 0.53532814, 0.44463454],
+                &[3.42525091, 0.62391373, 0.1688554],
+            )
+            .unwrap();
+        let assignment = BasisSetAssignmentType::Atom("H".to_string());
+
+        let mut output = Vec::<u8>::new();
+        write_basis_set(&assignment, &basis_set, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "spherical
+
+****
+H     0
+S    3   1.00
+          3.42525091           0.15432897
+          0.62391373           0.53532814
+           0.1688554           0.44463454
+****
+"
+        );
+
+        basis_set.set_spherical(SphericalOrCartesian::Cartesian);
+        let mut output = Vec::<u8>::new();
+        write_basis_set(&assignment, &basis_set, &mut output).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("cartesian\n\n****\nH     0\n"));
+    }
+}
@@ -0,0 +1,311 @@
+use std::{error::Error, io::Write};
+
+use super::{writer::check_angular_momentums, BasisSetParseError};
+use crate::details::{
+    angular_momentum::AngularMomentum, atomic_basis_set::AtomicBasisSet,
+    basis_set_assignment::BasisSetAssignmentType,
+};
+
+/// Position within the `*`-delimited sections of a `$basis` block
+#[derive(PartialEq)]
+enum Section {
+    // Before the line naming the element and the basis set
+    AtomHeader,
+    // Between the atom header and the `*` opening its shells
+    AtomHeaderEnd,
+    Shells,
+}
+
+/// Shell being read, complete once it holds as many rows as its `3  s` header counts
+struct PendingShell {
+    angular_momentum: AngularMomentum,
+    num_primitives: usize,
+    rows: Vec<Vec<f64>>,
+}
+
+fn parse_shell_header(line: &str, line_number: usize) -> Result<PendingShell, BasisSetParseError> {
+    // Number of primitives and shell letter, e.g. "   3  s"
+    let split: Vec<&str> = line.split_whitespace().collect();
+    if split.len() != 2 || split[1].chars().count() != 1 {
+        return Err(BasisSetParseError::BadCgtoHeader {
+            line: line_number,
+            text: line.to_string(),
+        });
+    }
+    let num_primitives =
+        split[0]
+            .parse::<usize>()
+            .map_err(|_| BasisSetParseError::BadCgtoHeader {
+                line: line_number,
+                text: line.to_string(),
+            })?;
+    let angular_momentum =
+        AngularMomentum::try_from(split[1].chars().next().unwrap()).map_err(|_| {
+            BasisSetParseError::UnknownAngularMomentum {
+                line: line_number,
+                text: line.to_string(),
+            }
+        })?;
+    Ok(PendingShell {
+        angular_momentum,
+        num_primitives,
+        rows: vec![],
+    })
+}
+
+/// Reads the `$basis` block of a Turbomole `basis` file, returning one basis set per atom
+/// section. Sections are delimited by `*` lines, the first holding the element and the basis
+/// set name, e.g. `h def-SV(P)`, the second the shells. Shell headers hold the number of
+/// primitives followed by the shell letter, then rows of exponental and coefficient.
+pub fn read_basis_set(
+    stream: &mut dyn Iterator<Item = Result<String, std::io::Error>>,
+) -> Result<Vec<(BasisSetAssignmentType, AtomicBasisSet)>, Box<dyn Error>> {
+    let mut basis_sets: Vec<(BasisSetAssignmentType, AtomicBasisSet)> = vec![];
+    let mut in_basis_block = false;
+    let mut section = Section::AtomHeader;
+    let mut pending_shell: Option<PendingShell> = None;
+
+    for (index, item) in stream.enumerate() {
+        let line_number = index + 1;
+        let line = item.map_err(BasisSetParseError::Io)?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if !in_basis_block {
+            in_basis_block = trimmed.starts_with("$basis");
+            section = Section::AtomHeader;
+            continue;
+        }
+        if let Some(shell) = pending_shell.as_mut() {
+            if trimmed.starts_with('$') || trimmed.starts_with('*') {
+                return Err(Box::new(BasisSetParseError::UnexpectedEof));
+            }
+            let row = trimmed
+                .split_whitespace()
+                .map(|value| value.parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| BasisSetParseError::BadFloat {
+                    line: line_number,
+                    text: line.clone(),
+                })?;
+            // An exponental followed by coefficients, as many in every row of the shell
+            let num_columns_expected = match shell.rows.first() {
+                Some(first_row) => row.len() == first_row.len(),
+                None => row.len() >= 2,
+            };
+            if !num_columns_expected {
+                return Err(Box::new(BasisSetParseError::BadColumnCount {
+                    line: line_number,
+                    text: line,
+                }));
+            }
+            shell.rows.push(row);
+            if shell.rows.len() == shell.num_primitives {
+                let shell = pending_shell.take().unwrap();
+                let (_, basis_set) = basis_sets.last_mut().unwrap();
+                basis_set.add_combined_shell(&[shell.angular_momentum], &shell.rows)?;
+            }
+            continue;
+        }
+
+        // The next data group, e.g. `$ecp` or `$end`, closes the block
+        if trimmed.starts_with('$') {
+            if section != Section::AtomHeader {
+                return Err(Box::new(BasisSetParseError::UnexpectedEof));
+            }
+            in_basis_block = false;
+            continue;
+        }
+        if trimmed.starts_with('*') {
+            section = match section {
+                Section::AtomHeaderEnd => Section::Shells,
+                Section::AtomHeader | Section::Shells => Section::AtomHeader,
+            };
+            continue;
+        }
+        match section {
+            Section::AtomHeader => {
+                let element = trimmed.split_whitespace().next().unwrap_or_default();
+                basis_sets.push((
+                    BasisSetAssignmentType::Atom(element.to_string()),
+                    AtomicBasisSet::new(),
+                ));
+                section = Section::AtomHeaderEnd;
+            }
+            Section::AtomHeaderEnd => {
+                return Err(Box::new(BasisSetParseError::BadDeclaration {
+                    line: line_number,
+                    text: line,
+                }))
+            }
+            Section::Shells => {
+                let shell = parse_shell_header(trimmed, line_number)?;
+                if shell.num_primitives > 0 {
+                    pending_shell = Some(shell);
+                }
+            }
+        }
+    }
+
+    if pending_shell.is_some() || in_basis_block {
+        return Err(Box::new(BasisSetParseError::UnexpectedEof));
+    }
+    Ok(basis_sets)
+}
+
+/// Writes the basis set as a Turbomole `$basis` block, e.g.
+///
+/// ```text
+/// $basis
+/// *
+/// h def-SV(P)
+/// *
+///    3  s
+///           13.010701           0.01968216
+/// ...
+/// *
+/// $end
+/// ```
+///
+/// Turbomole spells element symbols and shell letters in lower case.
+pub fn write_basis_set(
+    assignment: &BasisSetAssignmentType,
+    basis_set_name: &str,
+    basis_set: &AtomicBasisSet,
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    check_angular_momentums(basis_set)?;
+    writeln!(writer, "$basis")?;
+    writeln!(writer, "*")?;
+    writeln!(
+        writer,
+        "{} {}",
+        assignment.to_string().to_lowercase(),
+        basis_set_name
+    )?;
+    writeln!(writer, "*")?;
+    for (angular_momentum, segmented_contraction) in basis_set {
+        writeln!(
+            writer,
+            "{:>4}  {}",
+            segmented_contraction.get_num_primitives(),
+            angular_momentum.symbol().to_ascii_lowercase()
+        )?;
+        for primitive in segmented_contraction {
+            writeln!(
+                writer,
+                "{:>20} {:>20}",
+                primitive.exponental(),
+                primitive.coefficient()
+            )?;
+        }
+    }
+    writeln!(writer, "*")?;
+    writeln!(writer, "$end")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::details::{
+        angular_momentum::AngularMomentum, basis_set_assignment::BasisSetAssignmentType,
+    };
+    use crate::io::BasisSetParseError;
+
+    use super::{read_basis_set, write_basis_set};
+
+    // def-SV(P) for H, from the Turbomole basis set library
+    const HYDROGEN_BASIS_SET: &str = "$basis
+*
+h def-SV(P)
+# hydrogen  (4s) / [2s]     {31}
+*
+   3  s
+     13.010701000      0.19682158000E-01
+      1.9622572000     0.13796524000
+      0.44453796000    0.47831935000
+   1  s
+      0.12194962000     1.0000000000
+*
+$end
+";
+
+    #[test]
+    fn test_read_hydrogen_basis_set() {
+        let basis_sets = read_basis_set(&mut Cursor::new(HYDROGEN_BASIS_SET).lines()).unwrap();
+        assert_eq!(basis_sets.len(), 1);
+        let (assignment, basis_set) = &basis_sets[0];
+        assert_eq!(*assignment, BasisSetAssignmentType::Atom("h".to_string()));
+        assert_eq!(basis_set.get_num_contracted_functions(), 2);
+        assert_eq!(basis_set.get_num_gaussian_primitives(), 4);
+        assert_eq!(basis_set.get_highest_angular_momentum(), AngularMomentum::S);
+
+        let (_, contraction) = basis_set.into_iter().next().unwrap();
+        assert_abs_diff_eq!(contraction.get(0).unwrap().exponental(), 13.010701);
+        assert_abs_diff_eq!(contraction.get(0).unwrap().coefficient(), 0.019682158);
+    }
+
+    #[test]
+    fn test_round_trip_hydrogen_basis_set() {
+        let basis_sets = read_basis_set(&mut Cursor::new(HYDROGEN_BASIS_SET).lines()).unwrap();
+        let (assignment, basis_set) = &basis_sets[0];
+
+        let mut output = Vec::<u8>::new();
+        write_basis_set(assignment, "def-SV(P)", basis_set, &mut output).unwrap();
+        let written = String::from_utf8(output).unwrap();
+        assert!(written.starts_with("$basis\n*\nh def-SV(P)\n*\n   3  s\n"));
+
+        let round_trip = read_basis_set(&mut Cursor::new(written).lines()).unwrap();
+        assert_eq!(round_trip.len(), 1);
+        assert_eq!(round_trip[0].0, *assignment);
+        let primitives: Vec<(f64, f64)> = round_trip[0]
+            .1
+            .into_iter()
+            .flat_map(|(_, contraction)| contraction.iter())
+            .map(|primitive| (primitive.coefficient(), primitive.exponental()))
+            .collect();
+        let expected_primitives: Vec<(f64, f64)> = basis_set
+            .into_iter()
+            .flat_map(|(_, contraction)| contraction.iter())
+            .map(|primitive| (primitive.coefficient(), primitive.exponental()))
+            .collect();
+        assert_eq!(primitives, expected_primitives);
+    }
+
+    #[test]
+    fn test_read_truncated_shell() {
+        let error = read_basis_set(
+            &mut Cursor::new("$basis\n*\nh def-SV(P)\n*\n   2  s\n   3.0  0.5\n*\n$end\n").lines(),
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BasisSetParseError>(),
+            Some(BasisSetParseError::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn test_read_bad_column_count() {
+        for (input, line_number) in [
+            ("$basis\n*\nh def-SV(P)\n*\n   1  s\n   3.0\n*\n$end\n", 6),
+            (
+                "$basis\n*\nh def-SV(P)\n*\n   2  s\n   3.0  0.5  0.2\n   1.0  0.4\n*\n$end\n",
+                7,
+            ),
+        ] {
+            let error = read_basis_set(&mut Cursor::new(input).lines())
+                .err()
+                .unwrap();
+            assert!(matches!(
+                error.downcast_ref::<BasisSetParseError>(),
+                Some(BasisSetParseError::BadColumnCount { line, .. }) if *line == line_number
+            ));
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::io::Write;
+
+#[cfg(any(feature = "gaussian", feature = "nwchem", feature = "turbomole"))]
+use crate::details::{
+    angular_momentum::AngularMomentum, atomic_basis_set::BasisSetValidationError,
+};
+use crate::details::{
+    atomic_basis_set::AtomicBasisSet, basis_set_assignment::BasisSetAssignmentType,
+};
+
+/// Writes basis sets in one file format, so the format can be picked at runtime
+pub trait BasisSetWriter {
+    fn write_basis_set(
+        &self,
+        assignment: &BasisSetAssignmentType,
+        basis_set: &AtomicBasisSet,
+        writer: &mut dyn Write,
+    ) -> std::io::Result<()>;
+}
+
+/// Fails with `InvalidData` if a shell is stored beyond the highest supported angular momentum,
+/// as it has no letter to be written with
+#[cfg(any(feature = "gaussian", feature = "nwchem", feature = "turbomole"))]
+pub(crate) fn check_angular_momentums(basis_set: &AtomicBasisSet) -> std::io::Result<()> {
+    if basis_set.into_iter().any(|(angular_momentum, _)| {
+        angular_momentum == AngularMomentum::UnsupportedAngularMomentum
+    }) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            BasisSetValidationError::UnsupportedAngularMomentum,
+        ));
+    }
+    Ok(())
+}
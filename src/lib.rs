@@ -2,7 +2,13 @@ mod details;
 pub mod io;
 
 pub use details::{
-    angular_momentum::AngularMomentum,
-    atomic_basis_set::AtomicBasisSet,
+    angular_momentum::{AngularMomentum, AngularMomentumError},
+    atomic_basis_set::{
+        AtomicBasisSet, BasisSetDiff, BasisSetSummary, BasisSetValidationError,
+        SphericalOrCartesian,
+    },
+    basis_set_assignment::BasisSetAssignmentType,
+    ecp::{EcpTerm, EffectiveCorePotential},
     gaussian_exp::*,
+    library::BasisSetLibrary,
 };
\ No newline at end of file